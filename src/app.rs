@@ -0,0 +1,854 @@
+use std::ffi::{OsStr, OsString};
+use std::io;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+
+use termion::event::{Key, MouseButton, MouseEvent};
+use tui::layout::Rect;
+use tui::widgets::ListState;
+
+use rufile::bookmarks::Bookmarks;
+use rufile::chooser::ChooseMode;
+use rufile::command_input::input::{self, AppCommand, CommandHandler, InputMode};
+use rufile::command_input::jobs::{JobQueue, JobUpdate};
+use rufile::config::Config;
+use rufile::entry::file_data::display_name;
+use rufile::frecency::{self, Frecency};
+use rufile::keymap::{Action, Keymap};
+use rufile::search::finder::{Finder, WalkOptions};
+use rufile::search::grep::{self, Grep};
+use rufile::search::matcher::{MatchKind, Matcher};
+
+use crate::event::Event;
+use crate::keys::{KeyCommand, PendingKeys};
+use crate::results::{FindView, GrepView, ResultKind, Results};
+use crate::tab::Tab;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PaneLayout {
+    /// File list next to the preview and info panes.
+    TwoPane,
+    /// Parent, current and preview columns.
+    Miller,
+    /// Two file lists side by side, the inactive one showing `other`.
+    Dual,
+}
+
+pub struct App {
+    pub command: CommandHandler,
+    pub tabs: Vec<Tab>,
+    /// Index into `tabs` of the tab being shown.
+    pub active: usize,
+    /// Index into `tabs` of the tab shown in the inactive pane of the
+    /// dual-pane layout.
+    pub other: usize,
+    search_kind: MatchKind,
+    pub results: Option<Results>,
+    pub layout: PaneLayout,
+    pub find_kind: MatchKind,
+    pub find_options: WalkOptions,
+    pub bookmarks: Bookmarks,
+    pub frecency: Frecency,
+    /// Directory last recorded in `frecency`.
+    last_visit: PathBuf,
+    /// Selection in the bookmark popup, `None` while it is closed.
+    pub bookmark_list: Option<ListState>,
+    /// Copies, moves and deletes running in the background.
+    pub jobs: JobQueue,
+    /// Selection in the jobs popup, `None` while it is closed.
+    pub job_list: Option<ListState>,
+    /// Where jobs send their progress.
+    job_events: Sender<Event<Key>>,
+    /// Count and prefix keys typed so far in normal mode.
+    pub keys: PendingKeys,
+    /// Where the preview was last drawn, for the mouse wheel.
+    pub preview_area: Rect,
+    /// Lines the preview is scrolled down.
+    pub preview_scroll: u16,
+    /// File the preview is scrolled for; selecting another resets it.
+    previewed: Option<PathBuf>,
+    /// Time, tab and row of the last click, to detect double clicks.
+    last_click: Option<(Instant, usize, usize)>,
+    /// Set when rufile was started to pick files or a directory.
+    pub chooser: Option<ChooseMode>,
+    /// Files picked in `ChooseMode::Files`.
+    pub chosen: Vec<PathBuf>,
+    /// Set to leave the main loop, e.g. once files were picked.
+    pub quit: bool,
+    /// Set when quitting was refused because jobs are running.
+    pub quit_warned: bool,
+}
+
+/// Longest time between the clicks of a double click.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+
+/// Rows or lines moved per notch of the mouse wheel.
+const WHEEL_STEP: usize = 3;
+
+impl App {
+    pub fn new(
+        path: PathBuf,
+        config: &Config,
+        keymap: Keymap,
+        bookmarks: Bookmarks,
+        frecency: Frecency,
+        job_events: Sender<Event<Key>>,
+    ) -> App {
+        let mut command = CommandHandler::default();
+        command.background = true;
+
+        App {
+            command,
+            tabs: vec![Tab::new(path, &config.view)],
+            active: 0,
+            other: 0,
+            search_kind: MatchKind::Substring,
+            results: None,
+            layout: PaneLayout::TwoPane,
+            find_kind: MatchKind::Fuzzy,
+            find_options: WalkOptions::default(),
+            bookmarks,
+            frecency,
+            last_visit: PathBuf::new(),
+            bookmark_list: None,
+            jobs: JobQueue::default(),
+            job_list: None,
+            job_events,
+            keys: PendingKeys::new(keymap),
+            preview_area: Rect::default(),
+            preview_scroll: 0,
+            previewed: None,
+            last_click: None,
+            chooser: None,
+            chosen: vec![],
+            quit: false,
+            quit_warned: false,
+        }
+    }
+
+    pub fn tab(&self) -> &Tab {
+        &self.tabs[self.active]
+    }
+
+    pub fn tab_mut(&mut self) -> &mut Tab {
+        &mut self.tabs[self.active]
+    }
+
+    /// Opens a tab on the current directory and switches to it.
+    pub fn new_tab(&mut self) {
+        let tab = self.tab().duplicate();
+        self.tabs.insert(self.active + 1, tab);
+        self.switch_tab(self.active + 1);
+    }
+
+    /// Closes the active tab. The last tab can't be closed.
+    pub fn close_tab(&mut self) {
+        if self.tabs.len() == 1 {
+            return;
+        }
+
+        self.tabs.remove(self.active);
+        if self.other > self.active {
+            self.other -= 1;
+        }
+        self.active = self.active.min(self.tabs.len() - 1);
+        self.switch_tab(self.active);
+
+        if self.layout == PaneLayout::Dual {
+            if self.tabs.len() == 1 {
+                self.layout = PaneLayout::TwoPane;
+            } else {
+                self.pick_other_pane();
+            }
+        }
+    }
+
+    pub fn next_tab(&mut self) {
+        self.switch_tab((self.active + 1) % self.tabs.len());
+    }
+
+    pub fn prev_tab(&mut self) {
+        self.switch_tab((self.active + self.tabs.len() - 1) % self.tabs.len());
+    }
+
+    pub fn switch_tab(&mut self, idx: usize) {
+        if idx >= self.tabs.len() {
+            return;
+        }
+
+        self.clear_prompt();
+        if idx == self.other {
+            self.other = self.active;
+        }
+        self.active = idx;
+    }
+
+    /// Makes the inactive pane of the dual-pane layout the active one.
+    pub fn switch_pane(&mut self) {
+        if self.layout == PaneLayout::Dual {
+            self.switch_tab(self.other);
+        }
+    }
+
+    /// The tab shown next to the active one, if the layout has two panes.
+    pub fn other_tab(&self) -> Option<&Tab> {
+        match self.layout {
+            PaneLayout::Dual => self.tabs.get(self.other),
+            _ => None,
+        }
+    }
+
+    /// Keeps `other` pointing at a tab other than the active one, opening
+    /// a second tab if there is only one.
+    fn pick_other_pane(&mut self) {
+        if self.tabs.len() == 1 {
+            let tab = self.tab().duplicate();
+            self.tabs.push(tab);
+        }
+
+        if self.other == self.active || self.other >= self.tabs.len() {
+            self.other = (self.active + 1) % self.tabs.len();
+        }
+    }
+
+    /// Runs a command typed in normal mode. Quitting is left to the caller.
+    pub fn run(&mut self, command: KeyCommand) {
+        let times = command.times();
+
+        match command.action {
+            Action::Quit => {}
+            Action::Up if command.count.is_none() => self.tab_mut().on_up_pressed(),
+            Action::Down if command.count.is_none() => self.tab_mut().on_down_pressed(),
+            Action::Up => self.tab_mut().move_by(times, false),
+            Action::Down => self.tab_mut().move_by(times, true),
+            Action::Left => {
+                let result = self.tab_mut().on_left_pressed();
+                self.report(result);
+            }
+            Action::Right => self.open_selected(),
+            Action::Top => self.tab_mut().select_row(times - 1),
+            Action::Bottom => match command.count {
+                Some(line) => self.tab_mut().select_row(line - 1),
+                None => self.tab_mut().select_last(),
+            },
+            Action::HalfPageDown => self.tab_mut().move_half_page(times, true),
+            Action::HalfPageUp => self.tab_mut().move_half_page(times, false),
+            Action::ScreenTop => self.tab_mut().select_on_screen(Some(times - 1)),
+            Action::ScreenMiddle => self.tab_mut().select_on_screen(None),
+            Action::ScreenBottom => self.tab_mut().select_from_bottom(times - 1),
+            Action::CycleSort => self.tab_mut().cycle_sort_key(),
+            Action::ReverseSort => self.tab_mut().toggle_sort_reverse(),
+            Action::DirsFirst => self.tab_mut().toggle_dirs_first(),
+            Action::IgnoreCase => self.tab_mut().toggle_ignore_case(),
+            Action::ToggleHidden => self.tab_mut().toggle_hidden(),
+            Action::ToggleIgnores => self.tab_mut().toggle_ignores(),
+            Action::ToggleLayout => self.toggle_layout(),
+            Action::ToggleTree => self.tab_mut().toggle_tree_view(),
+            Action::SwitchPane => self.switch_pane(),
+            Action::Search => self.start_search(),
+            Action::SearchNext => self.tab_mut().search_next(true),
+            Action::SearchPrev => self.tab_mut().search_next(false),
+            Action::ClearFilter => {
+                if !self.tab_mut().cancel_visual() {
+                    self.clear_filter();
+                }
+            }
+            Action::Find => self.start_find(),
+            Action::Command => {
+                self.command.input = String::from(":");
+                self.command.input_mode = InputMode::Editing;
+            }
+            Action::Copy => self.run_command(":c"),
+            Action::Cut => self.run_command(":m"),
+            Action::Paste => self.run_command(":p"),
+            Action::Delete => self.run_command(":d"),
+            Action::ToggleSelect => self.tab_mut().toggle_select(times),
+            Action::VisualSelect => self.tab_mut().toggle_visual(),
+            Action::SelectAll => self.tab_mut().select_all(),
+            Action::InvertSelection => self.tab_mut().invert_selection(),
+            Action::ClearSelection => self.tab_mut().clear_selection(),
+            Action::SelectGlob => {
+                self.command.input = String::from(":s ");
+                self.command.input_mode = InputMode::Editing;
+            }
+            Action::Bookmarks => self.open_bookmarks(),
+            Action::Jobs => self.open_jobs(),
+            Action::SetMark => match command.arg {
+                Some(c) if c.is_ascii_alphabetic() => self.set_bookmark(&c.to_string()),
+                _ => {}
+            },
+            Action::GotoMark => {
+                if let Some(c) = command.arg {
+                    self.goto_bookmark(&c.to_string());
+                }
+            }
+            Action::Back => self.go_back(),
+            Action::Forward => self.go_forward(),
+            Action::NewTab => self.new_tab(),
+            Action::CloseTab => self.close_tab(),
+            Action::NextTab => self.next_tab(),
+            Action::PrevTab => self.prev_tab(),
+            Action::Tab(idx) => self.switch_tab(idx),
+        }
+    }
+
+    /// Selects the clicked entry, opens it on a double click and scrolls
+    /// the list or preview under the wheel.
+    pub fn on_mouse(&mut self, event: MouseEvent) {
+        if self.results.is_some() || self.bookmark_list.is_some() || self.job_list.is_some() {
+            return;
+        }
+
+        let (button, x, y) = match event {
+            // termion counts from 1
+            MouseEvent::Press(button, x, y) => (button, x.saturating_sub(1), y.saturating_sub(1)),
+            _ => return,
+        };
+
+        let in_preview = contains(self.preview_area, x, y);
+        match button {
+            MouseButton::WheelUp if in_preview => {
+                self.preview_scroll = self.preview_scroll.saturating_sub(WHEEL_STEP as u16);
+                return;
+            }
+            MouseButton::WheelDown if in_preview => {
+                self.preview_scroll = self.preview_scroll.saturating_add(WHEEL_STEP as u16);
+                return;
+            }
+            _ => {}
+        }
+
+        let mut panes = vec![self.active];
+        if self.layout == PaneLayout::Dual {
+            panes.push(self.other);
+        }
+        let tab_idx = match panes.into_iter().find(|&idx| contains(self.tabs[idx].list_area, x, y)) {
+            Some(idx) => idx,
+            None => return,
+        };
+
+        match button {
+            MouseButton::WheelUp => self.tabs[tab_idx].move_by(WHEEL_STEP, false),
+            MouseButton::WheelDown => self.tabs[tab_idx].move_by(WHEEL_STEP, true),
+            MouseButton::Left => {
+                let row = match self.tabs[tab_idx].row_at(x, y) {
+                    Some(row) => row,
+                    None => return,
+                };
+
+                if tab_idx != self.active {
+                    self.switch_tab(tab_idx);
+                }
+                self.tab_mut().select_row(row);
+
+                let now = Instant::now();
+                let double = matches!(self.last_click,
+                    Some((time, idx, last_row)) if idx == tab_idx && last_row == row
+                        && now.duration_since(time) < DOUBLE_CLICK);
+                if double {
+                    self.last_click = None;
+                    self.open_selected();
+                } else {
+                    self.last_click = Some((now, tab_idx, row));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Enters the selected directory or opens the selected file, which
+    /// in `ChooseMode::Files` picks it instead.
+    fn open_selected(&mut self) {
+        if self.chooser == Some(ChooseMode::Files) {
+            let marked = self.tab_mut().marked_paths();
+            let tab = self.tab();
+            if !marked.is_empty() {
+                self.chosen = marked;
+                self.quit = true;
+                return;
+            }
+            if let Some(file) = tab.selected_file().filter(|file| !file.is_dir()) {
+                self.chosen = vec![tab.path.join(file.raw_name())];
+                self.quit = true;
+                return;
+            }
+        }
+
+        let result = self.tab_mut().on_right_pressed();
+        self.report(result);
+    }
+
+    fn run_command(&mut self, command: &str) {
+        self.command.input = command.to_string();
+        self.call_command();
+    }
+
+    /// Brings state derived from the active tab up to date before drawing.
+    pub fn update(&mut self) {
+        // other tabs may have opened a directory since, e.g. falling back
+        // from a deleted one
+        self.tab().enter().ok();
+        self.tab_mut().update_preview();
+        if let Some(results) = &mut self.results {
+            results.update_context();
+        }
+
+        let selected = self.tab().selected_file().map(|file| self.tab().path.join(file.raw_name()));
+        if selected != self.previewed {
+            self.previewed = selected;
+            self.preview_scroll = 0;
+        }
+
+        if self.tab().path != self.last_visit {
+            self.last_visit = self.tab().path.clone();
+            self.frecency.add(&self.last_visit, frecency::now());
+        }
+    }
+
+    pub fn go_back(&mut self) {
+        if let Err(err) = self.tab_mut().go_back() {
+            self.command.fail(err.to_string());
+        }
+    }
+
+    pub fn go_forward(&mut self) {
+        if let Err(err) = self.tab_mut().go_forward() {
+            self.command.fail(err.to_string());
+        }
+    }
+
+    /// Opens the most frecent directory matching `fragments`.
+    pub fn jump(&mut self, fragments: &[String]) {
+        let fragments: Vec<&str> = fragments.iter().map(String::as_str).collect();
+        let target = self.frecency
+            .query(&fragments, &self.tab().path, frecency::now())
+            .map(|path| path.to_path_buf());
+
+        let result = match target {
+            Some(dir) => self.tab_mut().goto(dir, None).map_err(|err| err.to_string()),
+            None => Err(format!("no visited directory matches {}", fragments.join(" "))),
+        };
+        if let Err(message) = result {
+            self.command.fail(message);
+        }
+    }
+
+    /// Picks up changes reported by the directory watches.
+    pub fn refresh(&mut self) {
+        for tab in &mut self.tabs {
+            if let Err(err) = tab.refresh() {
+                self.command.fail(err.to_string());
+            }
+        }
+
+        if let Some(results) = &mut self.results {
+            results.poll();
+        }
+    }
+
+    pub fn toggle_layout(&mut self) {
+        self.layout = match self.layout {
+            PaneLayout::TwoPane => PaneLayout::Miller,
+            PaneLayout::Miller => PaneLayout::Dual,
+            PaneLayout::Dual => PaneLayout::TwoPane,
+        };
+        if self.layout == PaneLayout::Dual {
+            self.pick_other_pane();
+        }
+
+        let columns = self.layout == PaneLayout::Miller;
+        for tab in &mut self.tabs {
+            tab.set_columns(columns);
+        }
+    }
+
+    /// Text shown in front of the input line for prompts that don't keep
+    /// their prefix in `command.input`.
+    pub fn prompt(&self) -> String {
+        match self.command.input_mode {
+            InputMode::Find => format!(
+                "find ({}, depth {}, hidden {}): ",
+                self.find_kind,
+                self.find_options.max_depth,
+                if self.find_options.show_hidden { "on" } else { "off" }
+            ),
+            InputMode::Normal if self.tab().is_visual() => format!("-- VISUAL -- {}", self.keys),
+            InputMode::Normal => self.keys.to_string(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn start_find(&mut self) {
+        self.command.input.drain(..);
+        self.command.input_mode = InputMode::Find;
+    }
+
+    pub fn cycle_find_kind(&mut self) {
+        self.find_kind = self.find_kind.next();
+    }
+
+    pub fn change_find_depth(&mut self, deeper: bool) {
+        let depth = &mut self.find_options.max_depth;
+        if deeper {
+            *depth += 1;
+        } else if *depth > 1 {
+            *depth -= 1;
+        }
+    }
+
+    pub fn toggle_find_hidden(&mut self) {
+        self.find_options.show_hidden = !self.find_options.show_hidden;
+    }
+
+    /// Starts walking the tree below the current directory.
+    pub fn run_find(&mut self) {
+        let pattern: String = self.command.input.drain(..).collect();
+
+        let matcher = match Matcher::new(self.find_kind, &pattern) {
+            Ok(matcher) => matcher,
+            Err(err) => {
+                self.command.fail(err.to_string());
+                return;
+            }
+        };
+        self.command.input_mode = InputMode::Normal;
+
+        self.results = Some(Results::new(ResultKind::Find(FindView {
+            finder: Finder::spawn(self.tab().path.clone(), matcher, self.find_options),
+            pattern,
+            kind: self.find_kind,
+            results: vec![],
+        })));
+    }
+
+    /// Searches the contents of the files below the current directory.
+    pub fn run_grep(&mut self, pattern: &str, ignore_case: bool) {
+        let regex = match grep::build_regex(pattern, ignore_case) {
+            Ok(regex) => regex,
+            Err(err) => {
+                self.command.fail(err.to_string());
+                return;
+            }
+        };
+
+        // unlike find, look at every file shown by the listing, however deep
+        let options = WalkOptions {
+            max_depth: usize::MAX,
+            show_hidden: self.tab().visibility.show_hidden,
+        };
+        self.results = Some(Results::new(ResultKind::Grep(GrepView {
+            grep: Grep::spawn(self.tab().path.clone(), regex.clone(), options),
+            regex,
+            show_hidden: options.show_hidden,
+            hits: vec![],
+        })));
+    }
+
+    /// Stops the search and goes back to the directory listing.
+    pub fn close_results(&mut self) {
+        self.clear_prompt();
+        self.results = None;
+    }
+
+    pub fn move_results_selection(&mut self, down: bool) {
+        if let Some(results) = &mut self.results {
+            results.move_selection(down);
+        }
+    }
+
+    /// Opens the directory containing the selected result and puts the
+    /// cursor on it.
+    pub fn goto_result(&mut self) {
+        let target = match self.results.as_ref().and_then(Results::selected_path) {
+            Some(target) => target,
+            None => return,
+        };
+
+        let (dir, name) = match (target.parent(), target.file_name()) {
+            (Some(dir), Some(name)) => (dir.to_path_buf(), display_name(name)),
+            _ => return,
+        };
+
+        self.close_results();
+        let result = self.tab_mut().goto(dir, Some(name));
+        self.report(result);
+    }
+
+    pub fn start_search(&mut self) {
+        self.command.input.drain(..);
+        self.command.input.push('/');
+        self.command.input_mode = InputMode::Search;
+        let filter = Matcher::new(self.search_kind, "").ok();
+        self.tab_mut().set_filter(filter);
+    }
+
+    /// Re-filters the listing with the pattern typed so far.
+    pub fn update_search(&mut self) {
+        let pattern = self.command.input.strip_prefix('/').unwrap_or("").to_string();
+        // An incomplete regex keeps the last valid filter until it compiles.
+        let filter = match Matcher::new(self.search_kind, &pattern) {
+            Ok(matcher) => Some(matcher),
+            Err(_) => self.tab().filter.clone(),
+        };
+        self.tab_mut().set_filter(filter);
+    }
+
+    pub fn cycle_search_kind(&mut self) {
+        self.search_kind = self.search_kind.next();
+        self.update_search();
+    }
+
+    /// Leaves the search prompt, keeping the listing filtered.
+    pub fn confirm_search(&mut self) {
+        self.clear_prompt();
+        self.tab_mut().confirm_filter();
+    }
+
+    /// Restores the full listing with the cursor kept on the selected entry.
+    pub fn clear_filter(&mut self) {
+        self.clear_prompt();
+        self.tab_mut().clear_filter();
+    }
+
+    /// Bookmarks the current directory as `name`.
+    pub fn set_bookmark(&mut self, name: &str) {
+        let path = self.tab().path.clone();
+        if let Err(err) = self.bookmarks.set(name, &path) {
+            self.command.fail(format!("can't save bookmarks: {}", err));
+        }
+    }
+
+    /// Opens the bookmarked directory. A target that is gone or not a
+    /// directory anymore only flags an error.
+    pub fn goto_bookmark(&mut self, name: &str) {
+        let path = match self.bookmarks.get(name) {
+            Some(path) if path.is_dir() => path.to_path_buf(),
+            Some(path) => {
+                self.command.fail(format!("bookmark {}: {} is missing", name, path.display()));
+                return;
+            }
+            None => {
+                self.command.fail(format!("no bookmark {}", name));
+                return;
+            }
+        };
+
+        self.close_results();
+        if let Err(err) = self.tab_mut().goto(path, None) {
+            self.command.fail(err.to_string());
+        }
+    }
+
+    pub fn open_bookmarks(&mut self) {
+        let mut list = ListState::default();
+        if !self.bookmarks.is_empty() {
+            list.select(Some(0));
+        }
+        self.bookmark_list = Some(list);
+    }
+
+    pub fn close_bookmarks(&mut self) {
+        self.bookmark_list = None;
+    }
+
+    pub fn move_bookmark_selection(&mut self, down: bool) {
+        let count = self.bookmarks.len();
+        if let Some(list) = &mut self.bookmark_list {
+            if let Some(selected) = list.selected() {
+                let next = if down { selected + 1 } else { selected + count - 1 };
+                list.select(Some(next % count));
+            }
+        }
+    }
+
+    fn selected_bookmark(&self) -> Option<String> {
+        let selected = self.bookmark_list.as_ref()?.selected()?;
+        self.bookmarks.iter().nth(selected).map(|(name, _)| name.to_string())
+    }
+
+    /// Jumps to the bookmark selected in the popup and closes it.
+    pub fn goto_selected_bookmark(&mut self) {
+        if let Some(name) = self.selected_bookmark() {
+            self.close_bookmarks();
+            self.goto_bookmark(&name);
+        }
+    }
+
+    pub fn delete_selected_bookmark(&mut self) {
+        let name = match self.selected_bookmark() {
+            Some(name) => name,
+            None => return,
+        };
+
+        if let Err(err) = self.bookmarks.remove(&name) {
+            self.command.fail(format!("can't save bookmarks: {}", err));
+        }
+
+        let count = self.bookmarks.len();
+        if let Some(list) = &mut self.bookmark_list {
+            match list.selected() {
+                _ if count == 0 => list.select(None),
+                Some(selected) if selected >= count => list.select(Some(count - 1)),
+                _ => {}
+            }
+        }
+    }
+
+    pub fn open_jobs(&mut self) {
+        let mut list = ListState::default();
+        if !self.jobs.entries().is_empty() {
+            list.select(Some(0));
+        }
+        self.job_list = Some(list);
+    }
+
+    pub fn close_jobs(&mut self) {
+        self.job_list = None;
+    }
+
+    pub fn move_job_selection(&mut self, down: bool) {
+        let count = self.jobs.entries().len();
+        if let Some(list) = &mut self.job_list {
+            if let Some(selected) = list.selected() {
+                let next = if down { selected + 1 } else { selected + count - 1 };
+                list.select(Some(next % count));
+            }
+        }
+    }
+
+    fn selected_job(&self) -> Option<usize> {
+        self.job_list.as_ref()?.selected()
+    }
+
+    pub fn toggle_pause_selected_job(&mut self) {
+        if let Some(idx) = self.selected_job() {
+            self.jobs.toggle_pause(idx);
+        }
+    }
+
+    pub fn cancel_selected_job(&mut self) {
+        if let Some(idx) = self.selected_job() {
+            self.jobs.cancel(idx);
+        }
+        self.start_next_job();
+    }
+
+    /// Removes the finished jobs from the popup.
+    pub fn clear_finished_jobs(&mut self) {
+        self.jobs.clear_finished();
+
+        let count = self.jobs.entries().len();
+        if let Some(list) = &mut self.job_list {
+            list.select(if count == 0 { None } else { Some(0) });
+        }
+    }
+
+    /// Whether rufile may quit. While jobs are running it only warns,
+    /// unless `confirmed` by quitting again right after the warning.
+    pub fn can_quit(&mut self, confirmed: bool) -> bool {
+        if confirmed || !self.jobs.is_busy() {
+            return true;
+        }
+
+        self.quit_warned = true;
+        self.command.fail("jobs are still running, quit again to cancel them");
+        false
+    }
+
+    /// Queues the jobs of the last command.
+    fn queue_jobs(&mut self) {
+        for job in self.command.take_jobs() {
+            self.jobs.push(job);
+        }
+        self.start_next_job();
+    }
+
+    fn start_next_job(&mut self) {
+        let tx = self.job_events.clone();
+        self.jobs.start_next(move |id, update| {
+            tx.send(Event::Job(id, update)).ok();
+        });
+    }
+
+    /// Records what a job reported, starting the next one once it ends.
+    pub fn on_job_update(&mut self, id: usize, update: JobUpdate) {
+        let finished = !matches!(update, JobUpdate::Progress(_));
+        if let JobUpdate::Failed(message) = &update {
+            let job = self.jobs.get(id).map(|entry| entry.job.to_string()).unwrap_or_default();
+            self.command.fail(format!("{} failed: {}", job, message));
+        }
+
+        self.jobs.update(id, update);
+        if finished {
+            self.start_next_job();
+        }
+    }
+
+    /// Runs the typed command. File operations act on the selected entries,
+    /// or on the one under the cursor if none are.
+    pub fn call_command(&mut self) {
+        let mut files: Vec<OsString> = self.tab_mut()
+            .marked_paths()
+            .into_iter()
+            .map(PathBuf::into_os_string)
+            .collect();
+        if files.is_empty() {
+            let tab = self.tab();
+            files.extend(tab.selected_file().map(|file| tab.path.join(file.raw_name()).into_os_string()));
+        }
+        let files: Vec<&OsStr> = files.iter().map(OsString::as_os_str).collect();
+
+        let op = self.command.input.split_ascii_whitespace().next().and_then(input::operation);
+
+        self.command.destination = self.other_tab().map(|tab| tab.path.clone());
+        self.tab().enter().ok();
+        let app_command = self.command.exec_on(&files);
+        self.queue_jobs();
+
+        // the executor keeps its own list of files to paste, the marks are used up
+        if matches!(op, Some('c' | 'm' | 'd' | 'e' | 'r')) && self.command.input_mode != InputMode::Error {
+            self.tab_mut().clear_selection();
+        }
+
+        match app_command {
+            Some(AppCommand::Grep { pattern, ignore_case }) => self.run_grep(&pattern, ignore_case),
+            Some(AppCommand::Bookmark { name }) => self.set_bookmark(&name),
+            Some(AppCommand::Jump { fragments }) => self.jump(&fragments),
+            Some(AppCommand::ChangeDir { path }) => {
+                if let Err(err) = self.tab_mut().goto(path, None) {
+                    self.command.fail(err.to_string());
+                }
+            }
+            Some(AppCommand::Select { pattern }) if self.tab_mut().select_glob(&pattern) == 0 => {
+                self.command.fail(format!("no entries match {}", pattern));
+            }
+            Some(AppCommand::Select { .. }) => {}
+            None => {}
+        }
+
+        // Don't wait for the watch to catch up with our own changes.
+        let result = self.tab_mut().reload();
+        self.report(result);
+        if self.layout == PaneLayout::Dual {
+            let result = self.tabs[self.other].reload();
+            self.report(result);
+        }
+    }
+
+    /// Shows why navigating failed in the status line.
+    fn report(&mut self, result: io::Result<()>) {
+        if let Err(err) = result {
+            self.command.fail(err.to_string());
+        }
+    }
+
+    fn clear_prompt(&mut self) {
+        self.command.input.drain(..);
+        self.command.input_mode = InputMode::Normal;
+    }
+}
+
+fn contains(area: Rect, x: u16, y: u16) -> bool {
+    area.x <= x && x < area.right() && area.y <= y && y < area.bottom()
+}
\ No newline at end of file
@@ -0,0 +1,141 @@
+use std::ffi::OsString;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+
+use crate::batch::Exec;
+use crate::chooser::{ChooseMode, Chooser};
+
+pub const USAGE: &str = "\
+Usage: rufile [OPTIONS] [PATH]
+
+Starts in PATH, or in the parent of PATH with the file selected. With
+--exec or -c the commands are run in PATH instead, without the interface.
+
+Options:
+  -H, --hidden         Show hidden files
+      --config <FILE>  Read the configuration from FILE
+  -r, --read-only      Refuse operations that modify files
+      --choose-files[=FILE]
+                       Write the paths of the files picked with Enter to
+                       FILE, or stdout, and exit
+      --choose-dir[=FILE]
+                       Write the current directory to FILE, or stdout, on quit
+  -0, --null           Separate chosen paths with NUL instead of newlines
+      --exec <FILE>    Run the commands in FILE, one per line, and exit
+  -c, --command <CMD>  Run the command CMD, e.g. ':n d build', and exit
+  -h, --help           Print this help and exit
+  -V, --version        Print the version and exit";
+
+/// Options given on the command line.
+#[derive(Debug, Default, PartialEq)]
+pub struct Args {
+    /// Directory to start in, or a file to select in its parent.
+    pub path: Option<PathBuf>,
+    pub show_hidden: bool,
+    /// Config file to read instead of the default one.
+    pub config: Option<PathBuf>,
+    pub read_only: bool,
+    /// Where to write picked files or the last directory.
+    pub choose: Option<Chooser>,
+    /// Scripts and commands to run instead of starting the interface.
+    pub exec: Vec<Exec>,
+    pub help: bool,
+    pub version: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArgsError {
+    UnknownOption(String),
+    MissingValue(String),
+    UnexpectedArgument(OsString),
+    Conflict(String, String),
+}
+
+impl Display for ArgsError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ArgsError::UnknownOption(option) => write!(f, "unknown option '{}'", option),
+            ArgsError::MissingValue(option) => write!(f, "option '{}' needs a value", option),
+            ArgsError::UnexpectedArgument(arg) => {
+                write!(f, "unexpected argument '{}'", arg.to_string_lossy())
+            }
+            ArgsError::Conflict(first, second) => {
+                write!(f, "options '{}' and '{}' can't be combined", first, second)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+impl Args {
+    /// Parses the arguments following the program name. Values are taken
+    /// as `--config FILE` or `--config=FILE`, except for the optional ones
+    /// of the chooser options, and everything after `--` is a path.
+    pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<Args, ArgsError> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        let mut options_done = false;
+        let mut null = false;
+
+        while let Some(arg) = args.next() {
+            let option = match arg.to_str() {
+                Some(option) if !options_done && option.starts_with('-') && option != "-" => option,
+                _ => {
+                    if parsed.path.is_some() {
+                        return Err(ArgsError::UnexpectedArgument(arg));
+                    }
+                    parsed.path = Some(PathBuf::from(arg));
+                    continue;
+                }
+            };
+
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(OsString::from(value))),
+                _ => (option, None),
+            };
+
+            match name {
+                "--" if value.is_none() => options_done = true,
+                "-H" | "--hidden" if value.is_none() => parsed.show_hidden = true,
+                "-r" | "--read-only" if value.is_none() => parsed.read_only = true,
+                "-h" | "--help" if value.is_none() => parsed.help = true,
+                "-V" | "--version" if value.is_none() => parsed.version = true,
+                "--config" | "--exec" | "-c" | "--command" => {
+                    let value = value
+                        .or_else(|| args.next())
+                        .ok_or_else(|| ArgsError::MissingValue(name.to_string()))?;
+                    match name {
+                        "--config" => parsed.config = Some(PathBuf::from(value)),
+                        "--exec" => parsed.exec.push(Exec::Script(PathBuf::from(value))),
+                        _ => parsed.exec.push(Exec::Command(value.to_string_lossy().into_owned())),
+                    }
+                }
+                "-0" | "--null" if value.is_none() => null = true,
+                "--choose-files" | "--choose-dir" => {
+                    if let Some(chooser) = &parsed.choose {
+                        let other = match chooser.mode {
+                            ChooseMode::Files => "--choose-files",
+                            ChooseMode::Dir => "--choose-dir",
+                        };
+                        return Err(ArgsError::Conflict(other.to_string(), name.to_string()));
+                    }
+
+                    let mode = if name == "--choose-files" { ChooseMode::Files } else { ChooseMode::Dir };
+                    parsed.choose = Some(Chooser {
+                        mode,
+                        output: value.filter(|value| value != "-").map(PathBuf::from),
+                        null: false,
+                    });
+                }
+                _ => return Err(ArgsError::UnknownOption(option.to_string())),
+            }
+        }
+
+        if let Some(chooser) = &mut parsed.choose {
+            chooser.null = null;
+        }
+
+        Ok(parsed)
+    }
+}
\ No newline at end of file
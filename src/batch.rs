@@ -0,0 +1,92 @@
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::{env, fs, io};
+
+use crate::command_input::input::{self, AppCommand, CommandHandler, InputMode};
+use crate::entry::file_data::path_error;
+
+/// Commands to run without the interface, in the order they were given.
+#[derive(Debug, PartialEq)]
+pub enum Exec {
+    /// A file with one command per line.
+    Script(PathBuf),
+    /// A single command line.
+    Command(String),
+}
+
+/// Operations that act on the selected file in the interface. In a batch
+/// the file is named by their first argument instead, e.g. `:r old new`.
+const FILE_OPERATIONS: [char; 5] = ['c', 'd', 'e', 'm', 'r'];
+
+/// Runs command lines against the working directory, for scripts.
+/// `:cd` changes the working directory for the lines that follow.
+#[derive(Default)]
+pub struct Batch {
+    command: CommandHandler,
+}
+
+impl Batch {
+    pub fn new(read_only: bool) -> Batch {
+        let mut batch = Batch::default();
+        batch.command.read_only = read_only;
+
+        batch
+    }
+
+    /// Runs a single line. Returns whether there was a command in it,
+    /// blank lines and `#` comments are skipped.
+    pub fn run(&mut self, line: &str) -> Result<bool, String> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(false);
+        }
+
+        let words: Vec<&str> = line.split_ascii_whitespace().collect();
+        let takes_file = input::operation(words[0]).is_some_and(|op| FILE_OPERATIONS.contains(&op));
+
+        let file_name = if takes_file {
+            let file_name = words.get(1).ok_or("missing file name")?;
+            let mut input = vec![words[0]];
+            input.extend(&words[2..]);
+            self.command.input = input.join(" ");
+            Some(OsStr::new(*file_name))
+        } else {
+            self.command.input = line.to_string();
+            None
+        };
+
+        match self.command.exec(file_name) {
+            Some(AppCommand::ChangeDir { path }) => {
+                env::set_current_dir(&path).map_err(|err| path_error(&path, err).to_string())?;
+            }
+            Some(_) => return Err("not available in batch mode".to_string()),
+            None => {}
+        }
+
+        if self.command.input_mode == InputMode::Error {
+            self.command.input_mode = InputMode::Normal;
+            return Err(self.command.error.take().unwrap_or_else(|| "invalid command".to_string()));
+        }
+
+        Ok(true)
+    }
+}
+
+/// The lines to run for `exec`, each with where it came from, e.g.
+/// `build.rf:3`.
+pub fn read_lines(exec: &[Exec]) -> io::Result<Vec<(String, String)>> {
+    let mut lines = vec![];
+    for item in exec {
+        match item {
+            Exec::Script(path) => {
+                let text = fs::read_to_string(path).map_err(|err| path_error(path, err))?;
+                for (idx, line) in text.lines().enumerate() {
+                    lines.push((format!("{}:{}", path.display(), idx + 1), line.to_string()));
+                }
+            }
+            Exec::Command(line) => lines.push(("-c".to_string(), line.clone())),
+        }
+    }
+
+    Ok(lines)
+}
\ No newline at end of file
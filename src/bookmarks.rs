@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, ConfigError};
+
+/// Named directories kept in `$XDG_DATA_HOME/rufile/bookmarks.toml`.
+/// Single-letter names double as the marks set with `m<letter>`.
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    /// File the bookmarks are saved to, `None` keeps them in memory only.
+    file: Option<PathBuf>,
+    entries: BTreeMap<String, PathBuf>,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct BookmarkFile {
+    bookmarks: BTreeMap<String, PathBuf>,
+}
+
+impl Bookmarks {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("rufile").join("bookmarks.toml"))
+    }
+
+    /// Loads the bookmarks saved in `file`. A missing file is not an error,
+    /// it is created on the first save.
+    pub fn load(file: &Path) -> Result<Bookmarks, ConfigError> {
+        let saved: BookmarkFile = config::load_toml(file)?;
+
+        Ok(Bookmarks {
+            file: Some(file.to_path_buf()),
+            entries: saved.bookmarks,
+        })
+    }
+
+    pub fn load_default() -> Result<Bookmarks, ConfigError> {
+        match Bookmarks::default_path() {
+            Some(file) => Bookmarks::load(&file),
+            None => Ok(Bookmarks::default()),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+
+        config::save_toml(file, &BookmarkFile { bookmarks: self.entries.clone() })
+    }
+
+    /// Adds or replaces the bookmark `name` and saves the bookmarks.
+    pub fn set(&mut self, name: &str, path: &Path) -> io::Result<()> {
+        self.entries.insert(name.to_string(), path.to_path_buf());
+        self.save()
+    }
+
+    pub fn remove(&mut self, name: &str) -> io::Result<()> {
+        if self.entries.remove(name).is_some() {
+            self.save()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Path> {
+        self.entries.get(name).map(PathBuf::as_path)
+    }
+
+    /// Bookmarks sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Path)> {
+        self.entries.iter().map(|(name, path)| (name.as_str(), path.as_path()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
\ No newline at end of file
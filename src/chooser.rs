@@ -0,0 +1,47 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// What rufile picks for the program that started it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChooseMode {
+    /// Files chosen with Enter, after which rufile exits.
+    Files,
+    /// The directory rufile was in when it quit.
+    Dir,
+}
+
+/// Where chosen paths are written to, for use from editors and shell
+/// functions.
+#[derive(Debug, PartialEq)]
+pub struct Chooser {
+    pub mode: ChooseMode,
+    /// File to write the paths to, stdout if `None`.
+    pub output: Option<PathBuf>,
+    /// Separate paths with NUL instead of newline bytes.
+    pub null: bool,
+}
+
+impl Chooser {
+    /// Each path followed by the separator, as raw bytes.
+    pub fn format(&self, paths: &[&Path]) -> Vec<u8> {
+        let separator = if self.null { b'\0' } else { b'\n' };
+        let mut bytes = vec![];
+        for path in paths {
+            bytes.extend_from_slice(path.as_os_str().as_bytes());
+            bytes.push(separator);
+        }
+
+        bytes
+    }
+
+    /// Writes `paths` to the output file, or to `stdout` if there is none.
+    pub fn write(&self, paths: &[&Path], stdout: &mut dyn Write) -> io::Result<()> {
+        let bytes = self.format(paths);
+        match &self.output {
+            Some(path) => File::create(path)?.write_all(&bytes),
+            None => stdout.write_all(&bytes),
+        }
+    }
+}
\ No newline at end of file
@@ -1,26 +1,57 @@
 use std::collections::HashMap;
-use std::io;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::{env, fs, io};
 
+use crate::entry::file_data::path_error;
+
+use super::jobs::{Job, JobControl};
 use super::operations::OperationExecutor;
+use super::path;
 
 #[derive(Debug, PartialEq)]
 pub enum InputMode {
     Editing,
     Normal,
     Error,
+    Search,
+    Find,
+}
+
+/// Commands that act on the file manager itself rather than on files.
+/// `CommandHandler::exec` only parses them and hands them to the caller.
+#[derive(Debug, PartialEq)]
+pub enum AppCommand {
+    Grep { pattern: String, ignore_case: bool },
+    Bookmark { name: String },
+    Jump { fragments: Vec<String> },
+    ChangeDir { path: PathBuf },
+    Select { pattern: String },
 }
 
+const APP_COMMANDS: [char; 4] = ['b', 'g', 's', 'z'];
+
 enum OperationError {
     InvalidArgument,
     OperationNotFound,
     WrongArgumentCount,
 }
 
-type Operation = fn(&mut OperationExecutor, args: Vec<&str>) -> io::Result<()>;
+type Operation = fn(&mut OperationExecutor, args: Vec<&OsStr>) -> io::Result<()>;
 
 pub struct CommandHandler {
     pub input: String,
     pub input_mode: InputMode,
+    /// Why the last command failed, shown in `InputMode::Error`.
+    pub error: Option<String>,
+    /// Where `:c` and `:m` put the file right away, e.g. the other pane's
+    /// directory. Without it they mark the file for a later `:p`.
+    pub destination: Option<PathBuf>,
+    /// Refuse file operations, e.g. when browsing someone else's files.
+    pub read_only: bool,
+    /// Leave copies, moves and deletes for the caller to take with
+    /// `take_jobs` instead of running them before returning.
+    pub background: bool,
     executor: OperationExecutor,
     operations: HashMap<char, Operation>,
 }
@@ -39,6 +70,10 @@ impl Default for CommandHandler {
         CommandHandler {
             input: String::new(),
             input_mode: InputMode::Normal,
+            error: None,
+            destination: None,
+            read_only: false,
+            background: false,
             executor: OperationExecutor::default(),
             operations: ops,
         }
@@ -46,42 +81,114 @@ impl Default for CommandHandler {
 }
 
 impl CommandHandler {
-    pub fn exec(&mut self, file_name: Option<&str>) {
+    /// Runs the command typed into `input`. File operations act on
+    /// `file_name`, the raw name of the selected entry.
+    pub fn exec(&mut self, file_name: Option<&OsStr>) -> Option<AppCommand> {
+        self.exec_on(file_name.as_slice())
+    }
+
+    /// Like `exec`, but copy, cut, delete and chmod act on each of `files`,
+    /// e.g. all selected entries.
+    pub fn exec_on(&mut self, files: &[&OsStr]) -> Option<AppCommand> {
+        self.error = None;
         let command :Vec<&str> = self.input
             .split_ascii_whitespace()
             .collect();
 
-        if command[0].len() != 2 {
-            self.input_mode = InputMode::Error;
+        let first = match command.first() {
+            Some(first) => *first,
+            None => {
+                self.input_mode = InputMode::Error;
+                return None;
+            }
+        };
+
+        if first == ":cd" {
+            let result = self.parse_change_dir();
             self.input.drain(..);
-            return;
+
+            return match result {
+                Ok(path) => {
+                    self.input_mode = InputMode::Normal;
+                    Some(AppCommand::ChangeDir { path })
+                }
+                Err(message) => {
+                    self.fail(message);
+                    None
+                }
+            };
         }
 
-        let op = command[0].chars().nth(1).unwrap();
+        let op = match operation(first) {
+            Some(op) => op,
+            None => {
+                self.input_mode = InputMode::Error;
+                self.input.drain(..);
+                return None;
+            }
+        };
+
+        if APP_COMMANDS.contains(&op) {
+            let result = self.parse_app_command(op);
+            self.input.drain(..);
+
+            return match result {
+                Ok(app_command) => {
+                    self.input_mode = InputMode::Normal;
+                    Some(app_command)
+                }
+                Err(_) => {
+                    self.input_mode = InputMode::Error;
+                    None
+                }
+            };
+        }
 
-        if file_name.is_none() && op != 'n' && op != 'p' {
+        if self.read_only && self.operations.contains_key(&op) {
+            self.fail("read-only mode");
+            self.input.drain(..);
+            return None;
+        }
+
+        if files.is_empty() && op != 'n' && op != 'p' {
             self.input_mode = InputMode::Error;
             self.input.drain(..);
-            return;
+            return None;
         } 
 
-        match self.validate_input(&command) {
+        if files.len() > 1 && op == 'r' {
+            self.fail("can't rename several files at once");
+            self.input.drain(..);
+            return None;
+        }
+
+        match self.validate_input(op, &command) {
             Ok(()) => {
-                let mut arguments: Vec<&str> = vec![];
-                
-                if op != 'n' && op != 'p' {
-                    arguments.push(file_name.unwrap());
+                let args: Vec<String> = command.iter().skip(1).map(|arg| arg.to_string()).collect();
+                let args: Vec<&OsStr> = args.iter().map(OsStr::new).collect();
+                let targets = if op == 'n' || op == 'p' { &[][..] } else { files };
+
+                if op == 'c' || op == 'm' {
+                    self.executor.clear_file_paths();
                 }
 
-                for arg in command.iter().skip(1) {
-                    arguments.push(arg);
-                } 
+                let result = if targets.is_empty() {
+                    self.run_operation(op, None, &args)
+                } else {
+                    targets.iter().try_for_each(|file| self.run_operation(op, Some(file), &args))
+                };
+                let result = match result {
+                    Ok(()) => self.run_jobs(),
+                    Err(err) => {
+                        // none of the files are touched if one of them can't be
+                        self.executor.take_jobs();
+                        Err(err)
+                    }
+                };
 
-                let function = self.operations.get(&op).unwrap();
-                
-                match function(&mut self.executor, arguments) {
+                match result {
                     Ok(_) => self.input_mode = InputMode::Normal,
-                    Err(_) => self.input_mode = InputMode::Error,
+                    Err(err) => self.fail(err.to_string()),
                 }
             }
             Err(_) => {
@@ -90,6 +197,138 @@ impl CommandHandler {
         }
 
         self.input.drain(..);
+
+        None
+    }
+
+    /// Runs the operation `op` on `file_name`, if it takes one.
+    fn run_operation(&mut self, op: char, file_name: Option<&OsStr>, args: &[&OsStr]) -> io::Result<()> {
+        let mut arguments: Vec<&OsStr> = vec![];
+        arguments.extend(file_name);
+        arguments.extend(args);
+
+        match (op, &self.destination) {
+            ('c' | 'm', Some(destination)) => {
+                // an explicit argument overrides the default destination
+                let dst_dir = args.first().map_or(destination.clone(), PathBuf::from);
+                if op == 'c' {
+                    self.executor.copy_into(arguments[0], &dst_dir)
+                } else {
+                    self.executor.move_into(arguments[0], &dst_dir)
+                }
+            }
+            _ => {
+                let function = self.operations.get(&op).unwrap();
+                function(&mut self.executor, arguments)
+            }
+        }
+    }
+
+    /// Takes the jobs queued by the commands run in the background.
+    pub fn take_jobs(&mut self) -> Vec<Job> {
+        self.executor.take_jobs()
+    }
+
+    /// Runs the queued jobs unless they are left to run in the background.
+    fn run_jobs(&mut self) -> io::Result<()> {
+        if self.background {
+            return Ok(());
+        }
+
+        self.executor
+            .take_jobs()
+            .iter()
+            .try_for_each(|job| job.run(&JobControl::default(), &mut |_| {}))
+    }
+
+    /// Switches to `InputMode::Error`, explaining why in the status line.
+    pub fn fail(&mut self, message: impl Into<String>) {
+        self.input_mode = InputMode::Error;
+        self.error = Some(message.into());
+    }
+
+    /// Completes the directory name being typed after `:cd`.
+    pub fn complete(&mut self) {
+        let arg = match self.input.strip_prefix(":cd ") {
+            Some(arg) => arg.trim_start(),
+            None => return,
+        };
+
+        let cwd = env::current_dir().unwrap_or_default();
+        if let Some(completed) = path::complete_dir(arg, &cwd) {
+            self.input = format!(":cd {}", completed);
+        }
+    }
+
+    /// Resolves the target of `:cd`, which defaults to the home directory
+    /// like in a shell.
+    fn parse_change_dir(&self) -> Result<PathBuf, String> {
+        let arg = self.input.trim_start()[3..].trim();
+        let target = if arg.is_empty() {
+            dirs::home_dir().ok_or("no home directory")?
+        } else {
+            path::expand(arg)?
+        };
+
+        let cwd = env::current_dir().map_err(|err| err.to_string())?;
+        let target = path::normalize(&cwd.join(target));
+        if !target.is_dir() {
+            return Err(format!("{}: not a directory", target.display()));
+        }
+        fs::read_dir(&target).map_err(|err| path_error(&target, err).to_string())?;
+
+        Ok(target)
+    }
+
+    /// Parses the arguments of an `AppCommand`. Unlike file operations,
+    /// they take the rest of the line verbatim so patterns may contain spaces.
+    fn parse_app_command(&self, op: char) -> Result<AppCommand, OperationError> {
+        let args = self.input.trim_start()[2..].trim();
+
+        match op {
+            'b' => {
+                if args.is_empty() {
+                    return Err(OperationError::WrongArgumentCount);
+                }
+
+                Ok(AppCommand::Bookmark { name: args.to_string() })
+            }
+            'g' => {
+                let (ignore_case, pattern) = match args.strip_prefix("-i") {
+                    Some(pattern) if pattern.is_empty() || pattern.starts_with(' ') => {
+                        (true, pattern.trim_start())
+                    }
+                    _ => (false, args),
+                };
+                if pattern.is_empty() {
+                    return Err(OperationError::WrongArgumentCount);
+                }
+
+                Ok(AppCommand::Grep {
+                    pattern: pattern.to_string(),
+                    ignore_case,
+                })
+            }
+            's' => {
+                if args.is_empty() {
+                    return Err(OperationError::WrongArgumentCount);
+                }
+
+                Ok(AppCommand::Select { pattern: args.to_string() })
+            }
+            'z' => {
+                let fragments: Vec<String> = args
+                    .split_ascii_whitespace()
+                    .map(str::to_string)
+                    .collect();
+                if fragments.is_empty() {
+                    return Err(OperationError::WrongArgumentCount);
+                }
+
+                Ok(AppCommand::Jump { fragments })
+            }
+            _ => Err(OperationError::OperationNotFound),
+        }
     }
 
     fn validate_permissions(&self, perms: &str) -> Result<(), OperationError> {
@@ -98,7 +337,7 @@ impl CommandHandler {
         }
 
         for p in perms.chars() {
-            if p > '7' || p < '0' {
+            if !('0'..='7').contains(&p) {
                 return Err(OperationError::InvalidArgument);
             }
         }
@@ -106,13 +345,17 @@ impl CommandHandler {
         Ok(())
     }
 
-    fn validate_input(&self, command: &Vec<&str>) -> Result<(), OperationError> {
-        let operation = command[0].chars().nth(1).unwrap();
+    fn validate_input(&self, operation: char, command: &[&str]) -> Result<(), OperationError> {
         if !self.operations.contains_key(&operation) {
             return Err(OperationError::OperationNotFound);
         }
 
         match operation {
+            'c' | 'm' if self.destination.is_some() => {
+                if command.len() > 2 {
+                    return Err(OperationError::WrongArgumentCount);
+                }
+            }
             'c' | 'd' | 'm' | 'p' => {
                 if command.len() != 1 {
                     return Err(OperationError::WrongArgumentCount);
@@ -142,4 +385,15 @@ impl CommandHandler {
 
         Ok(())
     }
+}
+
+/// The operation a command word like `:d` stands for.
+pub fn operation(word: &str) -> Option<char> {
+    let op = word.strip_prefix(':')?;
+    let mut chars = op.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(op), None) => Some(op),
+        _ => None,
+    }
 }
\ No newline at end of file
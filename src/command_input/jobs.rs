@@ -0,0 +1,520 @@
+use std::collections::HashSet;
+use std::fmt::{self, Display, Formatter};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::entry::file_data::{display_name, path_error};
+
+/// How often a running job reports its progress.
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Files are copied in chunks of this size, checking for pause and cancel
+/// in between.
+const CHUNK_SIZE: usize = 256 * 1024;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobKind {
+    Copy,
+    Move,
+    Delete,
+}
+
+impl Display for JobKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(match self {
+            JobKind::Copy => "copy",
+            JobKind::Move => "move",
+            JobKind::Delete => "delete",
+        })
+    }
+}
+
+/// A file operation that may take a while, e.g. copying a large directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Job {
+    pub kind: JobKind,
+    /// Absolute paths of the entries to copy, move or delete.
+    pub sources: Vec<PathBuf>,
+    /// Directory the sources are copied or moved into, `None` for deletes.
+    pub dst_dir: Option<PathBuf>,
+}
+
+impl Display for Job {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} ", self.kind)?;
+        match self.sources.as_slice() {
+            [source] => write!(f, "{}", display_name(source.file_name().unwrap_or_default()))?,
+            sources => write!(f, "{} entries", sources.len())?,
+        }
+        if let Some(dst_dir) = &self.dst_dir {
+            write!(f, " to {}", display_name(dst_dir.as_os_str()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// How far a job got. Files count every entry but directories, bytes only
+/// those of regular files being copied.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Progress {
+    pub files_done: u64,
+    pub files_total: u64,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    /// Time spent working so far, without pauses.
+    pub elapsed: Duration,
+}
+
+impl Progress {
+    /// Share of the work done, between 0 and 1. Goes by bytes when there
+    /// are any to copy and by files otherwise.
+    pub fn ratio(&self) -> f64 {
+        let (done, total) = if self.bytes_total > 0 {
+            (self.bytes_done, self.bytes_total)
+        } else {
+            (self.files_done, self.files_total)
+        };
+        if total == 0 {
+            return 0.0;
+        }
+
+        (done as f64 / total as f64).min(1.0)
+    }
+
+    /// Time left at the speed so far, unknown until some work is done.
+    pub fn eta(&self) -> Option<Duration> {
+        let ratio = self.ratio();
+        if ratio <= 0.0 {
+            return None;
+        }
+
+        Some(self.elapsed.mul_f64((1.0 - ratio) / ratio))
+    }
+}
+
+/// What a job sends back to whoever started it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobUpdate {
+    Progress(Progress),
+    Done,
+    Cancelled,
+    Failed(String),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum ControlState {
+    #[default]
+    Run,
+    Pause,
+    Cancel,
+}
+
+/// Pauses, resumes or cancels a job from another thread. The job checks
+/// it between chunks, so it stops within one chunk.
+#[derive(Clone, Debug, Default)]
+pub struct JobControl {
+    state: Arc<(Mutex<ControlState>, Condvar)>,
+}
+
+impl JobControl {
+    fn set(&self, state: ControlState) {
+        let (lock, changed) = &*self.state;
+        let mut current = lock.lock().unwrap();
+        // a cancelled job stays cancelled
+        if *current != ControlState::Cancel {
+            *current = state;
+        }
+        changed.notify_all();
+    }
+
+    fn get(&self) -> ControlState {
+        *self.state.0.lock().unwrap()
+    }
+
+    pub fn pause(&self) {
+        self.set(ControlState::Pause);
+    }
+
+    pub fn resume(&self) {
+        self.set(ControlState::Run);
+    }
+
+    pub fn cancel(&self) {
+        self.set(ControlState::Cancel);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.get() == ControlState::Pause
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.get() == ControlState::Cancel
+    }
+
+    /// Blocks while the job is paused and fails once it is cancelled.
+    /// Returns how long it was paused.
+    fn wait(&self) -> io::Result<Duration> {
+        let (lock, changed) = &*self.state;
+        let started = Instant::now();
+        let mut state = lock.lock().unwrap();
+        while *state == ControlState::Pause {
+            state = changed.wait(state).unwrap();
+        }
+        if *state == ControlState::Cancel {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"));
+        }
+
+        Ok(started.elapsed())
+    }
+}
+
+impl Job {
+    pub fn new(kind: JobKind, sources: Vec<PathBuf>, dst_dir: Option<PathBuf>) -> Job {
+        Job { kind, sources, dst_dir }
+    }
+
+    /// Runs the job on the calling thread, passing its progress to `report`
+    /// every so often and once more at the end.
+    ///
+    /// An entry being copied when the job fails or is cancelled is removed
+    /// again, so a move leaves its source whole.
+    pub fn run(&self, control: &JobControl, report: &mut dyn FnMut(&Progress)) -> io::Result<()> {
+        let mut runner = Runner::new(self.kind, control, report);
+        // a job paused or cancelled while queued doesn't start
+        runner.checkpoint()?;
+
+        let targets: Vec<(&PathBuf, Option<PathBuf>)> = self.sources
+            .iter()
+            .map(|src| (src, self.dst_dir.as_ref().map(|dir| dir.join(src.file_name().unwrap_or_default()))))
+            .collect();
+
+        // check them all first, so that nothing is moved if one can't be
+        let mut seen = HashSet::new();
+        for dst in targets.iter().filter_map(|(_, dst)| dst.as_ref()) {
+            if fs::symlink_metadata(dst).is_ok() || !seen.insert(dst) {
+                return Err(path_error(dst, io::Error::new(io::ErrorKind::AlreadyExists,
+                    "destination already exists")));
+            }
+        }
+
+        let mut pending = vec![];
+        for (src, dst) in targets {
+            // no need to copy within the same file system
+            if let (JobKind::Move, Some(dst)) = (self.kind, &dst) {
+                if fs::rename(src, dst).is_ok() {
+                    continue;
+                }
+            }
+            pending.push((src, dst));
+        }
+
+        for (src, _) in &pending {
+            runner.count(src)?;
+        }
+
+        for (src, dst) in pending {
+            match dst {
+                Some(dst) => {
+                    if let Err(err) = runner.copy(src, &dst) {
+                        remove_all(&dst).ok();
+                        return Err(err);
+                    }
+                    if self.kind == JobKind::Move {
+                        remove_all(src).map_err(|err| path_error(src, err))?;
+                    }
+                }
+                None => runner.remove(src)?,
+            }
+        }
+
+        runner.finish();
+
+        Ok(())
+    }
+}
+
+/// Keeps the progress of a running job.
+struct Runner<'a> {
+    kind: JobKind,
+    control: &'a JobControl,
+    report: &'a mut dyn FnMut(&Progress),
+    progress: Progress,
+    started: Instant,
+    paused: Duration,
+    last_report: Option<Instant>,
+}
+
+impl<'a> Runner<'a> {
+    fn new(kind: JobKind, control: &'a JobControl, report: &'a mut dyn FnMut(&Progress)) -> Runner<'a> {
+        Runner {
+            kind,
+            control,
+            report,
+            progress: Progress::default(),
+            started: Instant::now(),
+            paused: Duration::ZERO,
+            last_report: None,
+        }
+    }
+
+    /// Waits out a pause, stops if cancelled and reports the progress if
+    /// it is time to.
+    fn checkpoint(&mut self) -> io::Result<()> {
+        self.paused += self.control.wait()?;
+        self.progress.elapsed = self.started.elapsed().saturating_sub(self.paused);
+
+        if self.last_report.is_none_or(|last| last.elapsed() >= REPORT_INTERVAL) {
+            (self.report)(&self.progress);
+            self.last_report = Some(Instant::now());
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        self.progress.elapsed = self.started.elapsed().saturating_sub(self.paused);
+        (self.report)(&self.progress);
+    }
+
+    /// Adds the entries below `path` to the totals.
+    fn count(&mut self, path: &Path) -> io::Result<()> {
+        let metadata = fs::symlink_metadata(path).map_err(|err| path_error(path, err))?;
+        if metadata.is_dir() {
+            for entry in fs::read_dir(path).map_err(|err| path_error(path, err))? {
+                self.count(&entry?.path())?;
+            }
+        } else {
+            self.progress.files_total += 1;
+            if metadata.is_file() && self.kind != JobKind::Delete {
+                self.progress.bytes_total += metadata.len();
+            }
+        }
+
+        self.checkpoint()
+    }
+
+    /// Copies `src` to `dst`, which mustn't exist. Symlinks are copied as
+    /// links.
+    fn copy(&mut self, src: &Path, dst: &Path) -> io::Result<()> {
+        let metadata = fs::symlink_metadata(src).map_err(|err| path_error(src, err))?;
+        if metadata.is_dir() {
+            fs::create_dir(dst).map_err(|err| path_error(dst, err))?;
+            for entry in fs::read_dir(src).map_err(|err| path_error(src, err))? {
+                let entry = entry?;
+                self.copy(&entry.path(), &dst.join(entry.file_name()))?;
+            }
+        } else if metadata.file_type().is_symlink() {
+            let target = fs::read_link(src).map_err(|err| path_error(src, err))?;
+            symlink(target, dst).map_err(|err| path_error(dst, err))?;
+            self.progress.files_done += 1;
+        } else {
+            self.copy_file(src, dst)?;
+            fs::set_permissions(dst, metadata.permissions()).map_err(|err| path_error(dst, err))?;
+            self.progress.files_done += 1;
+        }
+
+        self.checkpoint()
+    }
+
+    fn copy_file(&mut self, src: &Path, dst: &Path) -> io::Result<()> {
+        let mut input = File::open(src).map_err(|err| path_error(src, err))?;
+        let mut output = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(dst)
+            .map_err(|err| path_error(dst, err))?;
+
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            let read = input.read(&mut buf).map_err(|err| path_error(src, err))?;
+            if read == 0 {
+                break;
+            }
+            output.write_all(&buf[..read]).map_err(|err| path_error(dst, err))?;
+            self.progress.bytes_done += read as u64;
+            self.checkpoint()?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes `path` and everything below it.
+    fn remove(&mut self, path: &Path) -> io::Result<()> {
+        let metadata = fs::symlink_metadata(path).map_err(|err| path_error(path, err))?;
+        if metadata.is_dir() {
+            for entry in fs::read_dir(path).map_err(|err| path_error(path, err))? {
+                self.remove(&entry?.path())?;
+            }
+            fs::remove_dir(path).map_err(|err| path_error(path, err))?;
+        } else {
+            fs::remove_file(path).map_err(|err| path_error(path, err))?;
+            self.progress.files_done += 1;
+        }
+
+        self.checkpoint()
+    }
+}
+
+fn remove_all(path: &Path) -> io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Cancelled,
+    Failed(String),
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+pub struct JobEntry {
+    pub id: usize,
+    pub job: Job,
+    pub control: JobControl,
+    pub progress: Progress,
+    pub status: JobStatus,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Jobs run one at a time in the order they were added, each on a worker
+/// thread, so a paste and a later delete of the same files can't race.
+#[derive(Default)]
+pub struct JobQueue {
+    entries: Vec<JobEntry>,
+    next_id: usize,
+}
+
+impl JobQueue {
+    /// Adds `job` to the end of the queue and returns its id.
+    pub fn push(&mut self, job: Job) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push(JobEntry {
+            id,
+            job,
+            control: JobControl::default(),
+            progress: Progress::default(),
+            status: JobStatus::Queued,
+            handle: None,
+        });
+
+        id
+    }
+
+    pub fn entries(&self) -> &[JobEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, id: usize) -> Option<&JobEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Whether any job is queued or running.
+    pub fn is_busy(&self) -> bool {
+        self.entries.iter().any(|entry| !entry.status.is_finished())
+    }
+
+    /// Starts the oldest queued job unless one is running. `report` gets
+    /// the updates of the job with its id, on the worker thread.
+    pub fn start_next<F>(&mut self, mut report: F)
+    where
+        F: FnMut(usize, JobUpdate) + Send + 'static,
+    {
+        if self.entries.iter().any(|entry| entry.status == JobStatus::Running) {
+            return;
+        }
+        let entry = match self.entries.iter_mut().find(|entry| entry.status == JobStatus::Queued) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        let (id, job, control) = (entry.id, entry.job.clone(), entry.control.clone());
+        entry.status = JobStatus::Running;
+        entry.handle = Some(thread::spawn(move || {
+            let result = job.run(&control, &mut |progress| report(id, JobUpdate::Progress(progress.clone())));
+            report(id, match result {
+                Ok(()) => JobUpdate::Done,
+                Err(_) if control.is_cancelled() => JobUpdate::Cancelled,
+                Err(err) => JobUpdate::Failed(err.to_string()),
+            });
+        }));
+    }
+
+    /// Records an update sent by the job with `id`.
+    pub fn update(&mut self, id: usize, update: JobUpdate) {
+        let entry = match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => entry,
+            None => return,
+        };
+
+        entry.status = match update {
+            JobUpdate::Progress(progress) => {
+                entry.progress = progress;
+                return;
+            }
+            JobUpdate::Done => JobStatus::Done,
+            JobUpdate::Cancelled => JobStatus::Cancelled,
+            JobUpdate::Failed(message) => JobStatus::Failed(message),
+        };
+        if let Some(handle) = entry.handle.take() {
+            handle.join().ok();
+        }
+    }
+
+    /// Pauses the job at `idx` in `entries`, or resumes it if it is paused.
+    pub fn toggle_pause(&mut self, idx: usize) {
+        if let Some(entry) = self.entries.get(idx).filter(|entry| !entry.status.is_finished()) {
+            if entry.control.is_paused() {
+                entry.control.resume();
+            } else {
+                entry.control.pause();
+            }
+        }
+    }
+
+    /// Cancels the job at `idx` in `entries`. A queued job never starts, a
+    /// running one stops after its current chunk.
+    pub fn cancel(&mut self, idx: usize) {
+        if let Some(entry) = self.entries.get_mut(idx) {
+            entry.control.cancel();
+            if entry.status == JobStatus::Queued {
+                entry.status = JobStatus::Cancelled;
+            }
+        }
+    }
+
+    /// Forgets the jobs that are done, failed or cancelled.
+    pub fn clear_finished(&mut self) {
+        self.entries.retain(|entry| !entry.status.is_finished());
+    }
+
+    /// Cancels all jobs and waits for the running one to clean up, e.g.
+    /// before quitting.
+    pub fn cancel_all(&mut self) {
+        for idx in 0..self.entries.len() {
+            self.cancel(idx);
+        }
+        for entry in &mut self.entries {
+            if let Some(handle) = entry.handle.take() {
+                handle.join().ok();
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -1,2 +1,4 @@
 pub mod input;
-mod operations;
\ No newline at end of file
+pub mod jobs;
+mod operations;
+pub mod path;
\ No newline at end of file
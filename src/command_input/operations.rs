@@ -1,66 +1,58 @@
 use std::ffi::OsStr;
 use std::path::{Path, PathBuf};
-use std::{env, fs, io};
+use std::{env, fs, io, mem};
 use std::os::unix::fs::PermissionsExt;
 
+use super::jobs::{Job, JobKind};
+
 pub struct OperationExecutor {
     last_operation: char,
-    last_op_file_path: PathBuf,
+    /// Files copied or cut for the next paste.
+    last_op_file_paths: Vec<PathBuf>,
+    /// Copies, moves and deletes waiting to be run.
+    jobs: Vec<Job>,
 }
 
 impl Default for OperationExecutor {
     fn default() -> OperationExecutor {
         OperationExecutor {
             last_operation: '\0',
-            last_op_file_path: PathBuf::new(),
+            last_op_file_paths: Vec::new(),
+            jobs: Vec::new(),
         }
     }
 }
 
 impl OperationExecutor {
-    fn save_file_path(&mut self, file_name: &str) -> io::Result<()> {
-        self.last_op_file_path.push(env::current_dir()?);
-        self.last_op_file_path.push(file_name);
+    fn save_file_path(&mut self, file_name: &OsStr) -> io::Result<()> {
+        self.last_op_file_paths.push(env::current_dir()?.join(file_name));
 
         Ok(())
     }
 
-    fn is_file(&self) -> bool {
-        self.last_op_file_path.is_file()
-    }
-
-    fn copy_recursively(&self, src: &Path, dst: &Path) -> io::Result<()> {
-        fs::create_dir_all(&dst)?;
-        for entry in fs::read_dir(src)? {
-            let entry = entry?;
-            let ty = entry.file_type()?;
-            if ty.is_dir() {
-                self.copy_recursively(&entry.path(), &dst.join(entry.file_name()))?;
-            } else {
-                fs::copy(entry.path(), dst.join(entry.file_name()))?;
-            }
-        }
-
-        Ok(())
+    /// Forgets the files copied or cut before, so the next ones replace
+    /// them instead of adding to them.
+    pub fn clear_file_paths(&mut self) {
+        self.last_op_file_paths.clear();
     }
 
-    fn copy_dir(&self, _dir_name: &OsStr) -> io::Result<()> {
-        let mut dst = env::current_dir()?;
-        dst.push(self.last_op_file_path.file_name().unwrap());
-
-        self.copy_recursively(&self.last_op_file_path, &dst)?;
-
-        Ok(())
+    /// Takes the jobs queued by copy, move and delete since the last call.
+    pub fn take_jobs(&mut self) -> Vec<Job> {
+        mem::take(&mut self.jobs)
     }
 
-    fn copy_file(&self, file_name: &OsStr) -> io::Result<()> {
-        let output = Path::new(file_name);
-        fs::copy(&self.last_op_file_path, output)?;
-
-        Ok(())
+    /// Queues a job, adding the sources to the last job instead if it does
+    /// the same, e.g. for each of several selected files.
+    fn queue(&mut self, job: Job) {
+        match self.jobs.last_mut() {
+            Some(last) if last.kind == job.kind && last.dst_dir == job.dst_dir => {
+                last.sources.extend(job.sources);
+            }
+            _ => self.jobs.push(job),
+        }
     }
 
-    pub fn copy(&mut self, args: Vec<&str>) -> io::Result<()> {
+    pub fn copy(&mut self, args: Vec<&OsStr>) -> io::Result<()> {
         let file_name = args[0];
 
         self.save_file_path(file_name)?;
@@ -69,7 +61,7 @@ impl OperationExecutor {
         Ok(())
     }
 
-    pub fn cut(&mut self, args: Vec<&str>) -> io::Result<()> {
+    pub fn cut(&mut self, args: Vec<&OsStr>) -> io::Result<()> {
         let file_name = args[0];
 
         self.save_file_path(file_name)?;
@@ -78,54 +70,81 @@ impl OperationExecutor {
         Ok(())
     }
 
-    pub fn paste(&mut self, _args: Vec<&str>) -> io::Result<()> {
-        let file = self.last_op_file_path.file_name().unwrap();
-
-        if self.last_operation == 'c' {
-            if self.is_file() {
-                self.copy_file(&file)?;
-            } else { // copy directory
-                self.copy_dir(&file)?;
-            }
-        } else if self.last_operation == 'm' { // cut file
-            if self.is_file() {
-                self.copy_file(&file)?;
-                fs::remove_file(&self.last_op_file_path)?;
-            }  else { // move directory
-                self.copy_dir(&file)?;
-                fs::remove_dir_all(&self.last_op_file_path)?;
-            }
+    pub fn paste(&mut self, _args: Vec<&OsStr>) -> io::Result<()> {
+        let kind = match self.last_operation {
+            'c' => JobKind::Copy,
+            'm' => JobKind::Move,
+            _ => return Ok(()),
+        };
+
+        let dst_dir = env::current_dir()?;
+        let mut sources = vec![];
+        for file in &self.last_op_file_paths {
+            let (src, _) = self.transfer_paths(file.as_os_str(), &dst_dir)?;
+            sources.push(src);
         }
-        
+        self.queue(Job::new(kind, sources, Some(dst_dir)));
+
         self.last_operation = 'p';
 
         Ok(())
     }
 
-    pub fn delete(&mut self, args: Vec<&str>) -> io::Result<()> {
-        let file_name = args[0];
+    /// Copies `file_name` into `dst_dir` without waiting for a paste.
+    pub fn copy_into(&mut self, file_name: &OsStr, dst_dir: &Path) -> io::Result<()> {
+        let (src, _) = self.transfer_paths(file_name, dst_dir)?;
+        self.queue(Job::new(JobKind::Copy, vec![src], Some(dst_dir.to_path_buf())));
+
+        Ok(())
+    }
 
-        self.save_file_path(&file_name)?;
+    /// Moves `file_name` into `dst_dir`, copying it over when the directory
+    /// is on another file system.
+    pub fn move_into(&mut self, file_name: &OsStr, dst_dir: &Path) -> io::Result<()> {
+        let (src, _) = self.transfer_paths(file_name, dst_dir)?;
+        self.queue(Job::new(JobKind::Move, vec![src], Some(dst_dir.to_path_buf())));
 
-        if self.is_file() {
-            fs::remove_file(&file_name)?;
-        } else { // directory
-            fs::remove_dir_all(&file_name)?;
+        Ok(())
+    }
+
+    fn transfer_paths(&self, file_name: &OsStr, dst_dir: &Path) -> io::Result<(PathBuf, PathBuf)> {
+        let src = env::current_dir()?.join(file_name);
+        let dst = dst_dir.join(src.file_name().unwrap_or_default());
+        fs::symlink_metadata(&src)?;
+
+        // copying onto itself would truncate the file, into itself would
+        // never end
+        if dst.starts_with(&src) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                "destination is inside the source"));
+        }
+        if dst.exists() {
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists,
+                "destination already exists"));
         }
 
+        Ok((src, dst))
+    }
+
+    pub fn delete(&mut self, args: Vec<&OsStr>) -> io::Result<()> {
+        let path = env::current_dir()?.join(args[0]);
+        fs::symlink_metadata(&path)?;
+        self.queue(Job::new(JobKind::Delete, vec![path], None));
+
         Ok(())
     }
 
-    pub fn rename(&mut self, args: Vec<&str>) -> io::Result<()> {
-        let file_name = args[0];
-        let new_name = args[1];
+    pub fn rename(&mut self, args: Vec<&OsStr>) -> io::Result<()> {
+        let file_name = Path::new(args[0]);
+        // the new name is relative to the directory the file is in
+        let new_name = file_name.with_file_name(args[1]);
 
         fs::rename(file_name, new_name)?;
 
         Ok(())
     }
 
-    pub fn create(&mut self, args: Vec<&str>) -> io::Result<()> {
+    pub fn create(&mut self, args: Vec<&OsStr>) -> io::Result<()> {
         let file_name = args[1];
 
         if args[0] == "d" { // directory
@@ -137,9 +156,9 @@ impl OperationExecutor {
         Ok(())
     }
 
-    pub fn edit(&mut self, args: Vec<&str>) -> io::Result<()> {
+    pub fn edit(&mut self, args: Vec<&OsStr>) -> io::Result<()> {
         let file_name = args[0];
-        let modes = u32::from_str_radix(args[1], 8).unwrap();
+        let modes = u32::from_str_radix(&args[1].to_string_lossy(), 8).unwrap();
         fs::set_permissions(file_name, fs::Permissions::from_mode(modes))?;
 
         Ok(())
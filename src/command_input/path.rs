@@ -0,0 +1,100 @@
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Expands a leading `~` and `$VAR` or `${VAR}` references in a path
+/// typed on the command line. Fails with a message naming the variable
+/// or home directory that can't be resolved.
+pub fn expand(text: &str) -> Result<PathBuf, String> {
+    let mut expanded = String::new();
+    let mut rest = text;
+
+    if rest == "~" || rest.starts_with("~/") {
+        let home = dirs::home_dir().ok_or("no home directory")?;
+        expanded.push_str(&home.to_string_lossy());
+        rest = &rest[1..];
+    }
+
+    while let Some(start) = rest.find('$') {
+        expanded.push_str(&rest[..start]);
+        rest = &rest[start + 1..];
+
+        let (name, len) = match rest.strip_prefix('{') {
+            Some(braced) => {
+                let end = braced.find('}').ok_or("unterminated ${")?;
+                (&braced[..end], end + 2)
+            }
+            None => {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (&rest[..end], end)
+            }
+        };
+
+        if name.is_empty() {
+            expanded.push('$');
+            continue;
+        }
+        let value = env::var(name).map_err(|_| format!("${} is not set", name))?;
+        expanded.push_str(&value);
+        rest = &rest[len..];
+    }
+    expanded.push_str(rest);
+
+    Ok(PathBuf::from(expanded))
+}
+
+/// Removes `.` and `..` components and trailing slashes without
+/// resolving symlinks, the way a shell's `cd` does.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Completes the last component of `text` with the names of matching
+/// directories, relative to `cwd`. Several matches are completed to
+/// their common prefix, a single one gets a trailing `/`.
+pub fn complete_dir(text: &str, cwd: &Path) -> Option<String> {
+    let (dir_text, prefix) = match text.rfind('/') {
+        Some(idx) => (&text[..=idx], &text[idx + 1..]),
+        None => ("", text),
+    };
+
+    let dir = cwd.join(expand(dir_text).ok()?);
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.starts_with(prefix))
+        // dotfiles only when asked for
+        .filter(|name| prefix.starts_with('.') || !name.starts_with('.'))
+        .collect();
+    names.sort();
+
+    let completed = match names.as_slice() {
+        [] => return None,
+        [name] => format!("{}/", name),
+        [first, others @ ..] => others.iter().fold(first.clone(), |common, name| {
+            common
+                .chars()
+                .zip(name.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(c, _)| c)
+                .collect()
+        }),
+    };
+
+    Some(format!("{}{}", dir_text, completed))
+}
\ No newline at end of file
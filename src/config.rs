@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Settings read from `$XDG_CONFIG_HOME/rufile/config.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub view: ViewConfig,
+    /// Key sequences mapped to action names, on top of the default keymap.
+    pub keys: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ViewConfig {
+    /// Show entries whose names start with a dot.
+    pub show_hidden: bool,
+    /// Gitignore-style globs of entries to hide, e.g. `*.o` or `target/`.
+    pub ignore: Vec<String>,
+    /// Hide entries ignored by `.gitignore` files of the enclosing repository.
+    pub gitignore: bool,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    Parse(PathBuf, toml::de::Error),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            ConfigError::Parse(path, err) => write!(f, "{}: {}", path.display(), err),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("rufile").join("config.toml"))
+    }
+
+    pub fn load(path: &Path) -> Result<Config, ConfigError> {
+        let text = fs::read_to_string(path)
+            .map_err(|err| ConfigError::Io(path.to_path_buf(), err))?;
+
+        Config::parse(&text).map_err(|err| ConfigError::Parse(path.to_path_buf(), err))
+    }
+
+    /// Loads the config from its default location. A missing file is not
+    /// an error, the defaults are used instead.
+    pub fn load_default() -> Result<Config, ConfigError> {
+        match Config::default_path() {
+            Some(path) if path.exists() => Config::load(&path),
+            _ => Ok(Config::default()),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Config, toml::de::Error> {
+        toml::from_str(text)
+    }
+}
+
+/// Reads a TOML data file such as the bookmarks. A missing file is not an
+/// error, it reads as the default value.
+pub(crate) fn load_toml<T: DeserializeOwned + Default>(file: &Path) -> Result<T, ConfigError> {
+    match fs::read_to_string(file) {
+        Ok(text) => toml::from_str(&text).map_err(|err| ConfigError::Parse(file.to_path_buf(), err)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+        Err(err) => Err(ConfigError::Io(file.to_path_buf(), err)),
+    }
+}
+
+/// Writes `value` to the TOML data file `file`, creating its directory.
+pub(crate) fn save_toml<T: Serialize>(file: &Path, value: &T) -> io::Result<()> {
+    let text = toml::to_string(value)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(file, text)
+}
\ No newline at end of file
@@ -1,6 +1,9 @@
+use std::ffi::{OsStr, OsString};
 use std::io::{BufReader, Error, prelude::*};
-use std::fs::{DirEntry, File};
+use std::fs::{self, DirEntry, File, Metadata};
 use std::os::linux::fs::MetadataExt;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 use std::fmt::{self, Display, Formatter};
 use chrono::{DateTime, Local};
@@ -12,35 +15,71 @@ use super::type_parser::FileType;
 
 #[derive(Debug)]
 pub struct FileData {
+    /// The name for display and matching, see `display_name`.
     pub name: String,
+    /// The name as stored on disk, used to access the file.
+    raw_name: OsString,
+    /// Where the file is, so it can be read wherever the process is.
+    path: PathBuf,
     file_type: FileType,
     permissions: FilePermissions,
     mod_time: SystemTime,
     file_size: u64,
+    link: Option<Link>,
+}
+
+/// Where a symbolic link points.
+#[derive(Debug)]
+struct Link {
+    /// The target as stored in the link, possibly relative.
+    target: PathBuf,
+    /// Type, permissions, modification time and size of the file the
+    /// link resolves to, `None` if it's broken.
+    resolved: Option<(FileType, FilePermissions, SystemTime, u64)>,
 }
 
 impl FileData {
     pub fn new(entry: DirEntry) -> std::io::Result<FileData> {
+        // doesn't follow symlinks, so links are listed as links
         let metadata = entry.metadata()?;
         let file_type = FileType::new(metadata.st_mode());
         let permissions = FilePermissions::new(metadata.st_mode());
+
+        let link = if file_type == FileType::LNK {
+            Some(Link {
+                target: fs::read_link(entry.path())?,
+                resolved: fs::metadata(entry.path()).ok().and_then(|target| stat(&target).ok()),
+            })
+        } else {
+            None
+        };
         
+        let raw_name = entry.file_name();
+
         Ok(FileData {
-            name: entry.file_name().into_string().unwrap(),
+            name: display_name(&raw_name),
+            raw_name,
+            path: entry.path(),
             file_type,
             permissions,
             mod_time: metadata.modified()?,
             file_size: metadata.len(),
+            link,
         })
     }
 
     pub fn preview(&self) -> Result<String, Error> {
-        let file = File::open(&self.name)?;
+        self.preview_lines(10)
+    }
+
+    /// The first `count` lines of the file, or nothing if it isn't text.
+    pub fn preview_lines(&self, count: usize) -> Result<String, Error> {
+        let file = File::open(&self.path)?;
         let lines = BufReader::new(&file)
             .lines()
-            .take(10);
+            .take(count);
             
-        let mut head: Vec<String> = Vec::with_capacity(10);
+        let mut head: Vec<String> = Vec::with_capacity(count);
         
         for line in lines {
             if let Ok(line) = line {
@@ -54,7 +93,7 @@ impl FileData {
     }
 
     pub fn get_mime_type(&self) -> Result<String, FileMagicError> {
-        let file = &self.name;
+        let file = &self.path;
         let magic = magic!().expect("error");
   
         magic.file(file)
@@ -68,20 +107,146 @@ impl FileData {
         }
         let mod_time: DateTime<Local> = self.mod_time.into();
 
-        format!("{}{}\n{}\n{}\n{}", self.file_type, 
+        let mut info = format!("{}{}\n{}\n{}\n", self.file_type,
             self.permissions,
             format_size(self.file_size, DECIMAL),
-            mod_time.format("%b %e %T"),
-            mime_type)
+            mod_time.format("%b %e %T"));
+
+        if let Some(link) = &self.link {
+            info.push_str(&format!("-> {}\n", link.target.display()));
+            match &link.resolved {
+                Some((file_type, permissions, mod_time, file_size)) => {
+                    let mod_time: DateTime<Local> = (*mod_time).into();
+                    info.push_str(&format!("{}{}\n{}\n{}\n", file_type,
+                        permissions,
+                        format_size(*file_size, DECIMAL),
+                        mod_time.format("%b %e %T")));
+                }
+                None => info.push_str("broken link\n"),
+            }
+        }
+
+        info.push_str(&mime_type);
+        info
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.file_type
+    }
+
+    pub fn size(&self) -> u64 {
+        self.file_size
+    }
+
+    pub fn modified(&self) -> SystemTime {
+        self.mod_time
+    }
+
+    pub fn extension(&self) -> &str {
+        match self.name.rfind('.') {
+            Some(0) | None => "",
+            Some(idx) => &self.name[idx + 1..],
+        }
+    }
+
+    pub fn raw_name(&self) -> &OsStr {
+        &self.raw_name
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Names the entry by its path below `dir`, as for entries of an
+    /// expanded tree.
+    pub(crate) fn nest_in(&mut self, dir: &Path) {
+        self.raw_name = dir.join(&self.raw_name).into_os_string();
+        self.name = display_name(&self.raw_name);
     }
 
+    /// Whether this is a directory or a symlink to one.
     pub fn is_dir(&self) -> bool {
-        self.file_type == FileType::DIR
+        self.resolved_type() == Some(FileType::DIR)
     }
 
+    /// Whether this is a regular file or a symlink to one.
     pub fn is_file(&self) -> bool {
-        self.file_type == FileType::REG
+        self.resolved_type() == Some(FileType::REG)
     }
+
+    pub fn is_link(&self) -> bool {
+        self.link.is_some()
+    }
+
+    /// Whether this is a symlink whose target doesn't exist.
+    pub fn is_broken_link(&self) -> bool {
+        self.link.as_ref().is_some_and(|link| link.resolved.is_none())
+    }
+
+    /// The target of a symlink, as stored in the link.
+    pub fn link_target(&self) -> Option<&Path> {
+        self.link.as_ref().map(|link| link.target.as_path())
+    }
+
+    /// The type of the file after following a symlink, `None` for a
+    /// broken link.
+    fn resolved_type(&self) -> Option<FileType> {
+        match &self.link {
+            Some(link) => link.resolved.as_ref().map(|(file_type, ..)| *file_type),
+            None => Some(self.file_type),
+        }
+    }
+}
+
+/// Prefixes the message of `err` with the path it happened at, as io
+/// errors don't name it.
+pub fn path_error(path: &Path, err: Error) -> Error {
+    Error::new(err.kind(), format!("{}: {}", display_name(path.as_os_str()), err))
+}
+
+/// Converts a file name to UTF-8, writing bytes that aren't valid UTF-8
+/// as `\xNN` escapes. Backslashes are doubled, so a name that spells out
+/// an escape doesn't look like one and names stay unambiguous.
+pub fn display_name(name: &OsStr) -> String {
+    escape_name(name).0
+}
+
+/// Like `display_name`, also returning the positions of the chars that
+/// make up escapes, so they can be highlighted.
+pub fn escape_name(name: &OsStr) -> (String, Vec<usize>) {
+    let mut escaped = String::new();
+    let mut positions = vec![];
+    let mut chars = 0;
+
+    for chunk in name.as_bytes().utf8_chunks() {
+        for c in chunk.valid().chars() {
+            if c == '\\' {
+                escaped.push_str("\\\\");
+                positions.extend(chars..chars + 2);
+                chars += 2;
+            } else {
+                escaped.push(c);
+                chars += 1;
+            }
+        }
+
+        for byte in chunk.invalid() {
+            escaped.push_str(&format!("\\x{:02X}", byte));
+            positions.extend(chars..chars + 4);
+            chars += 4;
+        }
+    }
+
+    (escaped, positions)
+}
+
+fn stat(metadata: &Metadata) -> std::io::Result<(FileType, FilePermissions, SystemTime, u64)> {
+    Ok((
+        FileType::new(metadata.st_mode()),
+        FilePermissions::new(metadata.st_mode()),
+        metadata.modified()?,
+        metadata.len(),
+    ))
 }
 
 impl Display for FileData {
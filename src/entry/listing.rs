@@ -0,0 +1,173 @@
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+use inotify::{Inotify, WatchDescriptor, WatchMask};
+
+use super::file_data::FileData;
+use super::sort::SortOrder;
+
+pub fn read_dir(path: &Path) -> io::Result<Vec<FileData>> {
+    let mut files: Vec<FileData> = Vec::<FileData>::new();
+
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+
+        if let Ok(entry_data) = FileData::new(entry) {
+            files.push(entry_data);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Cached listing of a single directory.
+///
+/// The entries are read once when the directory is loaded and kept until
+/// the inotify watch on it reports a change or the listing is invalidated
+/// explicitly, e.g. after an operation.
+pub struct DirListing {
+    path: PathBuf,
+    files: Vec<FileData>,
+    sort: SortOrder,
+    inotify: Option<Inotify>,
+    watch: Option<WatchDescriptor>,
+    dirty: bool,
+}
+
+impl Default for DirListing {
+    fn default() -> DirListing {
+        DirListing {
+            path: PathBuf::new(),
+            files: Vec::new(),
+            sort: SortOrder::default(),
+            inotify: Inotify::init().ok(),
+            watch: None,
+            dirty: false,
+        }
+    }
+}
+
+impl DirListing {
+    /// Reads `path` and moves the inotify watch over to it.
+    pub fn load(&mut self, path: &Path) -> io::Result<()> {
+        let mut files = read_dir(path)?;
+        self.sort.sort(&mut files);
+
+        if self.path != path {
+            self.rewatch(path);
+            self.path = path.to_path_buf();
+        }
+        self.files = files;
+        self.dirty = false;
+
+        Ok(())
+    }
+
+    /// Marks the listing as stale so the next `refresh` reads it again.
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Drains pending inotify events and reloads the listing if it is stale.
+    /// Returns whether the entries were read again.
+    pub fn refresh(&mut self) -> io::Result<bool> {
+        if self.poll_changes() {
+            self.dirty = true;
+        }
+        // Deleting a directory that is still someone's working directory,
+        // e.g. ours, isn't reported to the watch.
+        if !self.path.as_os_str().is_empty() && !self.path.is_dir() {
+            self.dirty = true;
+        }
+
+        if !self.dirty {
+            return Ok(false);
+        }
+
+        let path = self.path.clone();
+        self.load(&path)?;
+
+        Ok(true)
+    }
+
+    /// Re-sorts the cached entries without reading the directory again.
+    pub fn set_sort(&mut self, sort: SortOrder) {
+        self.sort = sort;
+        self.sort.sort(&mut self.files);
+    }
+
+    pub fn sort_order(&self) -> SortOrder {
+        self.sort
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.files.iter().position(|file| file.name == name)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn files(&self) -> &[FileData] {
+        &self.files
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&FileData> {
+        self.files.get(idx)
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    fn rewatch(&mut self, path: &Path) {
+        if let Some(inotify) = &mut self.inotify {
+            if let Some(wd) = self.watch.take() {
+                inotify.watches().remove(wd).ok();
+            }
+
+            let mask = WatchMask::CREATE
+                | WatchMask::DELETE
+                | WatchMask::MODIFY
+                | WatchMask::ATTRIB
+                | WatchMask::MOVE
+                | WatchMask::DELETE_SELF
+                | WatchMask::MOVE_SELF;
+
+            // Without a watch (e.g. on some network file systems) the
+            // listing only refreshes when it is invalidated explicitly.
+            self.watch = inotify.watches().add(path, mask).ok();
+        }
+    }
+
+    fn poll_changes(&mut self) -> bool {
+        let inotify = match &mut self.inotify {
+            Some(inotify) => inotify,
+            None => return false,
+        };
+
+        let mut buffer = [0; 4096];
+        let mut changed = false;
+
+        loop {
+            match inotify.read_events(&mut buffer) {
+                Ok(events) => {
+                    // Events of a previous watch may still be queued.
+                    let current = self.watch.as_ref();
+                    if events.into_iter().any(|event| Some(&event.wd) == current) {
+                        changed = true;
+                    }
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => break,
+            }
+        }
+
+        changed
+    }
+}
@@ -1,4 +1,8 @@
 pub mod file_data;
+pub mod listing;
+pub mod sort;
+pub mod tree;
+pub mod visibility;
 
 pub mod permissions;
 pub mod type_parser;
\ No newline at end of file
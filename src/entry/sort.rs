@@ -0,0 +1,203 @@
+use std::cmp::Ordering;
+use std::fmt::{self, Display, Formatter};
+
+use super::file_data::FileData;
+use super::type_parser::FileType;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortKey {
+    Name,
+    Natural,
+    Size,
+    Modified,
+    Type,
+    Extension,
+}
+
+impl SortKey {
+    pub fn next(self) -> SortKey {
+        match self {
+            SortKey::Name => SortKey::Natural,
+            SortKey::Natural => SortKey::Size,
+            SortKey::Size => SortKey::Modified,
+            SortKey::Modified => SortKey::Type,
+            SortKey::Type => SortKey::Extension,
+            SortKey::Extension => SortKey::Name,
+        }
+    }
+}
+
+impl Display for SortKey {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            SortKey::Name => "name",
+            SortKey::Natural => "natural",
+            SortKey::Size => "size",
+            SortKey::Modified => "mtime",
+            SortKey::Type => "type",
+            SortKey::Extension => "extension",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SortOrder {
+    pub key: SortKey,
+    pub reverse: bool,
+    pub dirs_first: bool,
+    pub ignore_case: bool,
+}
+
+impl Default for SortOrder {
+    fn default() -> SortOrder {
+        SortOrder {
+            key: SortKey::Natural,
+            reverse: false,
+            dirs_first: true,
+            ignore_case: true,
+        }
+    }
+}
+
+impl SortOrder {
+    pub fn sort(&self, files: &mut [FileData]) {
+        files.sort_by(|a, b| self.compare(a, b));
+    }
+
+    pub fn compare(&self, a: &FileData, b: &FileData) -> Ordering {
+        if self.dirs_first && a.is_dir() != b.is_dir() {
+            return if a.is_dir() { Ordering::Less } else { Ordering::Greater };
+        }
+
+        let by_name = || self.compare_names(&a.name, &b.name);
+        let ordering = match self.key {
+            SortKey::Name => self.compare_strings(&a.name, &b.name),
+            SortKey::Natural => by_name(),
+            SortKey::Size => a.size().cmp(&b.size()).then_with(by_name),
+            SortKey::Modified => a.modified().cmp(&b.modified()).then_with(by_name),
+            SortKey::Type => type_rank(a.file_type())
+                .cmp(&type_rank(b.file_type()))
+                .then_with(by_name),
+            SortKey::Extension => self
+                .compare_strings(a.extension(), b.extension())
+                .then_with(by_name),
+        };
+
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    fn compare_strings(&self, a: &str, b: &str) -> Ordering {
+        if self.ignore_case {
+            lowercase(a).cmp(lowercase(b)).then_with(|| a.cmp(b))
+        } else {
+            a.cmp(b)
+        }
+    }
+
+    fn compare_names(&self, a: &str, b: &str) -> Ordering {
+        if self.ignore_case {
+            natural_cmp_chars(lowercase(a), lowercase(b)).then_with(|| a.cmp(b))
+        } else {
+            natural_cmp(a, b)
+        }
+    }
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.key)?;
+        if self.reverse {
+            write!(f, ", reversed")?;
+        }
+        if self.dirs_first {
+            write!(f, ", dirs first")?;
+        }
+        if !self.ignore_case {
+            write!(f, ", case sensitive")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares strings so that runs of digits are ordered by their numeric
+/// value, e.g. "file2" < "file10".
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    natural_cmp_chars(a.chars(), b.chars())
+}
+
+fn natural_cmp_chars<A, B>(a: A, b: B) -> Ordering
+where
+    A: Iterator<Item = char>,
+    B: Iterator<Item = char>,
+{
+    let mut a_chars = a.peekable();
+    let mut b_chars = b.peekable();
+
+    loop {
+        match (a_chars.peek(), b_chars.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_number(&mut a_chars);
+                let y = take_number(&mut b_chars);
+                let ordering = compare_numbers(&x, &y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(y);
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a_chars.next();
+                b_chars.next();
+            }
+        }
+    }
+}
+
+/// The characters of `s` in lower case, without allocating a new string.
+fn lowercase(s: &str) -> impl Iterator<Item = char> + '_ {
+    s.chars().flat_map(char::to_lowercase)
+}
+
+fn take_number<I: Iterator<Item = char>>(chars: &mut std::iter::Peekable<I>) -> String {
+    let mut number = String::new();
+    while let Some(c) = chars.next_if(|c| c.is_ascii_digit()) {
+        number.push(c);
+    }
+
+    number
+}
+
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let a_trimmed = a.trim_start_matches('0');
+    let b_trimmed = b.trim_start_matches('0');
+
+    a_trimmed.len().cmp(&b_trimmed.len())
+        .then_with(|| a_trimmed.cmp(b_trimmed))
+        // "01" after "1" keeps the ordering total
+        .then_with(|| a.len().cmp(&b.len()))
+}
+
+fn type_rank(file_type: FileType) -> u8 {
+    match file_type {
+        FileType::DIR => 0,
+        FileType::LNK => 1,
+        FileType::REG => 2,
+        FileType::FIFO => 3,
+        FileType::SOCK => 4,
+        FileType::CHR => 5,
+        FileType::BLK => 6,
+        FileType::UNDEFINED => 7,
+    }
+}
\ No newline at end of file
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::file_data::{display_name, FileData};
+use super::listing::read_dir;
+use super::sort::SortOrder;
+
+/// Directories expanded inline in a tree listing.
+///
+/// Children are read when their directory is expanded and named by their
+/// path relative to the root of the listing, e.g. `src/main.rs`, so they
+/// can be handed to operations and previews like top-level entries.
+#[derive(Default)]
+pub struct Tree {
+    children: HashMap<String, Expanded>,
+}
+
+struct Expanded {
+    /// The directory below the root, as named on disk.
+    path: PathBuf,
+    files: Vec<FileData>,
+}
+
+/// A line of the flattened tree.
+#[derive(Debug, PartialEq)]
+pub struct TreeRow {
+    /// Expanded directory the entry is in, `None` for top-level entries.
+    pub parent: Option<String>,
+    /// Index into the top-level entries or the children of `parent`.
+    pub idx: usize,
+    pub depth: usize,
+    /// For each ancestor level, whether the guide line of that level
+    /// continues below this row.
+    pub guides: Vec<bool>,
+    /// Whether this is the last entry of its directory.
+    pub last: bool,
+}
+
+impl Tree {
+    /// Reads the children of the directory `dir`, relative to `root`.
+    /// The expanded directory is then known by its display name.
+    pub fn expand(&mut self, root: &Path, dir: impl AsRef<Path>, sort: SortOrder) -> io::Result<()> {
+        let dir = dir.as_ref();
+        let mut files = read_dir(&root.join(dir))?;
+        for child in &mut files {
+            child.nest_in(dir);
+        }
+        sort.sort(&mut files);
+
+        let expanded = Expanded { path: dir.to_path_buf(), files };
+        self.children.insert(display_name(dir.as_os_str()), expanded);
+
+        Ok(())
+    }
+
+    /// Collapses `name` and everything expanded below it.
+    pub fn collapse(&mut self, name: &str) {
+        let prefix = Path::new(name);
+        self.children.retain(|dir, _| !Path::new(dir).starts_with(prefix));
+    }
+
+    pub fn is_expanded(&self, name: &str) -> bool {
+        self.children.contains_key(name)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.children.clear();
+    }
+
+    pub fn children(&self, name: &str) -> &[FileData] {
+        self.children.get(name).map_or(&[], |expanded| expanded.files.as_slice())
+    }
+
+    /// Reads all expanded directories again, collapsing those that are gone.
+    pub fn reload(&mut self, root: &Path, sort: SortOrder) {
+        let mut dirs: Vec<(String, PathBuf)> = self
+            .children
+            .iter()
+            .map(|(name, expanded)| (name.clone(), expanded.path.clone()))
+            .collect();
+        // parents first, so a vanished parent takes its children along
+        dirs.sort();
+
+        for (name, path) in dirs {
+            if self.is_expanded(&name) && self.expand(root, &path, sort).is_err() {
+                self.collapse(&name);
+            }
+        }
+    }
+
+    pub fn set_sort(&mut self, sort: SortOrder) {
+        for expanded in self.children.values_mut() {
+            sort.sort(&mut expanded.files);
+        }
+    }
+
+    /// Lists `top` with the children of expanded directories below their
+    /// parent, leaving out those for which `hidden` returns true.
+    pub fn flatten(&self, top: &[&FileData], hidden: impl Fn(&FileData) -> bool) -> Vec<TreeRow> {
+        let mut rows = vec![];
+        let top: Vec<_> = top.iter().copied().enumerate().collect();
+        self.push_rows(&mut rows, None, &top, &mut vec![], &hidden);
+
+        rows
+    }
+
+    fn push_rows(
+        &self,
+        rows: &mut Vec<TreeRow>,
+        parent: Option<&str>,
+        entries: &[(usize, &FileData)],
+        guides: &mut Vec<bool>,
+        hidden: &impl Fn(&FileData) -> bool,
+    ) {
+        for (position, &(idx, file)) in entries.iter().enumerate() {
+            let last = position + 1 == entries.len();
+            rows.push(TreeRow {
+                parent: parent.map(str::to_string),
+                idx,
+                depth: guides.len(),
+                guides: guides.clone(),
+                last,
+            });
+
+            if file.is_dir() && self.is_expanded(&file.name) {
+                let children: Vec<_> = self
+                    .children(&file.name)
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, child)| !hidden(child))
+                    .collect();
+
+                guides.push(!last);
+                self.push_rows(rows, Some(&file.name), &children, guides, hidden);
+                guides.pop();
+            }
+        }
+    }
+}
\ No newline at end of file
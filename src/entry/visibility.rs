@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+
+use crate::config::ViewConfig;
+
+use super::file_data::FileData;
+
+/// Decides which entries of a listing are hidden: dotfiles, entries
+/// matching the configured ignore globs and, optionally, entries ignored
+/// by git.
+#[derive(Clone)]
+pub struct Visibility {
+    pub show_hidden: bool,
+    /// Whether the ignore globs and `.gitignore` files are applied.
+    pub apply_ignores: bool,
+    use_gitignore: bool,
+    patterns: Vec<String>,
+    ignore: Gitignore,
+    /// `.gitignore` files from the current directory up to the repository
+    /// root, nearest first.
+    gitignores: Vec<Gitignore>,
+}
+
+impl Visibility {
+    pub fn new(config: &ViewConfig) -> Visibility {
+        Visibility {
+            show_hidden: config.show_hidden,
+            apply_ignores: true,
+            use_gitignore: config.gitignore,
+            patterns: config.ignore.clone(),
+            ignore: Gitignore::empty(),
+            gitignores: vec![],
+        }
+    }
+
+    /// Rebuilds the ignore rules for the listing of `dir`.
+    pub fn set_dir(&mut self, dir: &Path) {
+        let mut builder = GitignoreBuilder::new(dir);
+        for pattern in &self.patterns {
+            builder.add_line(None, pattern).ok();
+        }
+        self.ignore = builder.build().unwrap_or_else(|_| Gitignore::empty());
+
+        self.gitignores.clear();
+        if self.use_gitignore {
+            for ancestor in dir.ancestors() {
+                let (gitignore, _) = Gitignore::new(ancestor.join(".gitignore"));
+                if !gitignore.is_empty() {
+                    self.gitignores.push(gitignore);
+                }
+                if ancestor.join(".git").exists() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Whether `file` is left out of the listing of `dir`. Its name may
+    /// also be a path below `dir`, as for entries of an expanded tree.
+    pub fn is_hidden(&self, dir: &Path, file: &FileData) -> bool {
+        let name = file.name.rsplit('/').next().unwrap_or(&file.name);
+        if !self.show_hidden && name.starts_with('.') {
+            return true;
+        }
+        if !self.apply_ignores {
+            return false;
+        }
+
+        let path = dir.join(file.raw_name());
+        if self.ignore.matched(&path, file.is_dir()).is_ignore() {
+            return true;
+        }
+
+        for gitignore in &self.gitignores {
+            // rules of the current directory don't apply to its parent
+            if !path.starts_with(gitignore.path()) {
+                continue;
+            }
+            let matched = gitignore.matched_path_or_any_parents(&path, file.is_dir());
+            if matched.is_ignore() {
+                return true;
+            }
+            if matched.is_whitelist() {
+                return false;
+            }
+        }
+
+        false
+    }
+}
\ No newline at end of file
@@ -1,5 +1,8 @@
 use std::{io, sync::mpsc, thread, time::Duration};
-use termion::{event::Key, input::TermRead};
+use termion::event::{self as term_event, Key, MouseEvent};
+use termion::input::TermRead;
+
+use rufile::command_input::jobs::JobUpdate;
 
 pub struct EventConfig {
     tick_rate: Duration,
@@ -15,12 +18,17 @@ impl Default for EventConfig {
 
 pub enum Event<I> {
     Input(I),
+    Mouse(MouseEvent),
     Tick,
+    /// Progress or the end of the job with the given id.
+    Job(usize, JobUpdate),
 }
 
 #[derive(Debug)]
 pub struct Events {
     pub rx: mpsc::Receiver<Event<Key>>,
+    /// For background jobs to send their updates.
+    pub tx: mpsc::Sender<Event<Key>>,
 }
 
 impl Events {
@@ -32,21 +40,25 @@ impl Events {
         let (tx, rx) = mpsc::channel();
 
         let tx_cloned = tx.clone();
+        let tx_ticks = tx.clone();
         thread::spawn(move || {
             let stdin = io::stdin();
 
-            for evt in stdin.keys() {
-                if let Ok(key) = evt {
-                    if let Err(err) = tx_cloned.send(Event::Input(key)) {
-                        eprintln!("{}", err);
-                        return;
-                    }
+            for event in stdin.events().flatten() {
+                let event = match event {
+                    term_event::Event::Key(key) => Event::Input(key),
+                    term_event::Event::Mouse(mouse) => Event::Mouse(mouse),
+                    term_event::Event::Unsupported(_) => continue,
+                };
+                if let Err(err) = tx_cloned.send(event) {
+                    eprintln!("{}", err);
+                    return;
                 }
             }
         });
      
         thread::spawn(move || loop {
-            if let Err(err) = tx.send(Event::Tick) {
+            if let Err(err) = tx_ticks.send(Event::Tick) {
                 eprintln!("{}", err);
                 break;
             }
@@ -55,6 +67,7 @@ impl Events {
 
         Events {
             rx,
+            tx,
         }
     }
 }
@@ -0,0 +1,160 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::{self, ConfigError};
+
+/// Total rank above which all ranks are aged, so that old favourites
+/// make room for new ones.
+const MAX_RANK: f64 = 10000.0;
+
+const HOUR: u64 = 60 * 60;
+const DAY: u64 = 24 * HOUR;
+const WEEK: u64 = 7 * DAY;
+
+/// Visited directories ranked by how often and how recently they were
+/// visited, kept in `$XDG_DATA_HOME/rufile/frecency.toml`.
+#[derive(Debug, Default)]
+pub struct Frecency {
+    /// File the database is saved to, `None` keeps it in memory only.
+    file: Option<PathBuf>,
+    dirs: Vec<Visit>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Visit {
+    pub path: PathBuf,
+    pub rank: f64,
+    /// Seconds since the Unix epoch.
+    pub last: u64,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct FrecencyFile {
+    dirs: Vec<Visit>,
+}
+
+impl Visit {
+    /// The rank weighted by how long ago the directory was last visited.
+    pub fn score(&self, now: u64) -> f64 {
+        let age = now.saturating_sub(self.last);
+        let weight = if age < HOUR {
+            4.0
+        } else if age < DAY {
+            2.0
+        } else if age < WEEK {
+            0.5
+        } else {
+            0.25
+        };
+
+        self.rank * weight
+    }
+}
+
+impl Frecency {
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("rufile").join("frecency.toml"))
+    }
+
+    /// Loads the database saved in `file`. A missing file is not an error,
+    /// it is created on the first save.
+    pub fn load(file: &Path) -> Result<Frecency, ConfigError> {
+        let saved: FrecencyFile = config::load_toml(file)?;
+
+        Ok(Frecency {
+            file: Some(file.to_path_buf()),
+            dirs: saved.dirs,
+        })
+    }
+
+    pub fn load_default() -> Result<Frecency, ConfigError> {
+        match Frecency::default_path() {
+            Some(file) => Frecency::load(&file),
+            None => Ok(Frecency::default()),
+        }
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let file = match &self.file {
+            Some(file) => file,
+            None => return Ok(()),
+        };
+
+        config::save_toml(file, &FrecencyFile { dirs: self.dirs.clone() })
+    }
+
+    /// Records a visit of `path` at `now`.
+    pub fn add(&mut self, path: &Path, now: u64) {
+        match self.dirs.iter_mut().find(|visit| visit.path == path) {
+            Some(visit) => {
+                visit.rank += 1.0;
+                visit.last = now;
+            }
+            None => self.dirs.push(Visit {
+                path: path.to_path_buf(),
+                rank: 1.0,
+                last: now,
+            }),
+        }
+
+        let total: f64 = self.dirs.iter().map(|visit| visit.rank).sum();
+        if total > MAX_RANK {
+            for visit in &mut self.dirs {
+                visit.rank *= 0.9 * MAX_RANK / total;
+            }
+            self.dirs.retain(|visit| visit.rank >= 1.0);
+        }
+    }
+
+    /// The best scoring directory matching all `fragments`, skipping
+    /// `exclude` and directories that are gone.
+    ///
+    /// Fragments match case-insensitively and in order, the last one
+    /// within the final component, so `:z src` prefers `foo/src` over
+    /// `src/foo`.
+    pub fn query(&self, fragments: &[&str], exclude: &Path, now: u64) -> Option<&Path> {
+        self.dirs
+            .iter()
+            .filter(|visit| visit.path != exclude && matches(&visit.path, fragments))
+            .filter(|visit| visit.path.is_dir())
+            .max_by(|a, b| a.score(now).total_cmp(&b.score(now)))
+            .map(|visit| visit.path.as_path())
+    }
+
+    pub fn visits(&self) -> &[Visit] {
+        &self.dirs
+    }
+}
+
+fn matches(path: &Path, fragments: &[&str]) -> bool {
+    let path = path.to_string_lossy().to_lowercase();
+    let mut rest = path.as_str();
+
+    for fragment in fragments {
+        let fragment = fragment.to_lowercase();
+        match rest.find(&fragment) {
+            Some(idx) => rest = &rest[idx + fragment.len()..],
+            None => return false,
+        }
+    }
+
+    // the last fragment must be found after the last separator
+    match fragments.last() {
+        Some(last) => {
+            let name = path.rsplit('/').next().unwrap_or("");
+            name.contains(&last.to_lowercase())
+        }
+        None => true,
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
\ No newline at end of file
@@ -0,0 +1,366 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Display, Formatter};
+
+use termion::event::Key;
+
+/// Something a key binding can do in normal mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Up,
+    Down,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+    ScreenTop,
+    ScreenMiddle,
+    ScreenBottom,
+    CycleSort,
+    ReverseSort,
+    DirsFirst,
+    IgnoreCase,
+    ToggleHidden,
+    ToggleIgnores,
+    ToggleLayout,
+    ToggleTree,
+    SwitchPane,
+    Search,
+    SearchNext,
+    SearchPrev,
+    ClearFilter,
+    Find,
+    Command,
+    Copy,
+    Cut,
+    Paste,
+    Delete,
+    /// Selects or unselects the entry under the cursor and moves down.
+    ToggleSelect,
+    /// Starts selecting the entries the cursor moves over, or stops.
+    VisualSelect,
+    SelectAll,
+    InvertSelection,
+    ClearSelection,
+    /// Prompts for a glob of entries to select.
+    SelectGlob,
+    Bookmarks,
+    /// Opens the list of background copies, moves and deletes.
+    Jobs,
+    SetMark,
+    GotoMark,
+    Back,
+    Forward,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    /// Switches to the tab with the given index, counting from 0.
+    Tab(usize),
+}
+
+/// Names used for actions in the `[keys]` table of the config.
+const ACTION_NAMES: [(&str, Action); 47] = [
+    ("quit", Action::Quit),
+    ("up", Action::Up),
+    ("down", Action::Down),
+    ("left", Action::Left),
+    ("right", Action::Right),
+    ("top", Action::Top),
+    ("bottom", Action::Bottom),
+    ("half_page_down", Action::HalfPageDown),
+    ("half_page_up", Action::HalfPageUp),
+    ("screen_top", Action::ScreenTop),
+    ("screen_middle", Action::ScreenMiddle),
+    ("screen_bottom", Action::ScreenBottom),
+    ("cycle_sort", Action::CycleSort),
+    ("reverse_sort", Action::ReverseSort),
+    ("dirs_first", Action::DirsFirst),
+    ("ignore_case", Action::IgnoreCase),
+    ("toggle_hidden", Action::ToggleHidden),
+    ("toggle_ignores", Action::ToggleIgnores),
+    ("toggle_layout", Action::ToggleLayout),
+    ("toggle_tree", Action::ToggleTree),
+    ("switch_pane", Action::SwitchPane),
+    ("search", Action::Search),
+    ("search_next", Action::SearchNext),
+    ("search_prev", Action::SearchPrev),
+    ("clear_filter", Action::ClearFilter),
+    ("find", Action::Find),
+    ("command", Action::Command),
+    ("copy", Action::Copy),
+    ("cut", Action::Cut),
+    ("paste", Action::Paste),
+    ("delete", Action::Delete),
+    ("toggle_select", Action::ToggleSelect),
+    ("visual_select", Action::VisualSelect),
+    ("select_all", Action::SelectAll),
+    ("invert_selection", Action::InvertSelection),
+    ("clear_selection", Action::ClearSelection),
+    ("select_glob", Action::SelectGlob),
+    ("bookmarks", Action::Bookmarks),
+    ("jobs", Action::Jobs),
+    ("set_mark", Action::SetMark),
+    ("goto_mark", Action::GotoMark),
+    ("back", Action::Back),
+    ("forward", Action::Forward),
+    ("new_tab", Action::NewTab),
+    ("close_tab", Action::CloseTab),
+    ("next_tab", Action::NextTab),
+    ("prev_tab", Action::PrevTab),
+];
+
+/// Bindings used unless the config overrides them.
+const DEFAULT_BINDINGS: [(&str, &str); 61] = [
+    ("q", "quit"),
+    ("<C-c>", "quit"),
+    ("k", "up"),
+    ("<Up>", "up"),
+    ("j", "down"),
+    ("<Down>", "down"),
+    ("h", "left"),
+    ("<Left>", "left"),
+    ("l", "right"),
+    ("<Right>", "right"),
+    ("<CR>", "right"),
+    ("gg", "top"),
+    ("G", "bottom"),
+    ("<C-d>", "half_page_down"),
+    ("<C-u>", "half_page_up"),
+    ("H", "screen_top"),
+    ("M", "screen_middle"),
+    ("L", "screen_bottom"),
+    ("s", "cycle_sort"),
+    ("S", "reverse_sort"),
+    ("D", "dirs_first"),
+    ("I", "ignore_case"),
+    (".", "toggle_hidden"),
+    (",", "toggle_ignores"),
+    ("w", "toggle_layout"),
+    ("T", "toggle_tree"),
+    ("<Tab>", "switch_pane"),
+    ("/", "search"),
+    ("n", "search_next"),
+    ("N", "search_prev"),
+    ("<Esc>", "clear_filter"),
+    ("f", "find"),
+    (":", "command"),
+    ("yy", "copy"),
+    ("dd", "cut"),
+    ("pp", "paste"),
+    ("<Space>", "toggle_select"),
+    ("V", "visual_select"),
+    ("<C-a>", "select_all"),
+    ("*", "invert_selection"),
+    ("U", "clear_selection"),
+    ("+", "select_glob"),
+    ("b", "bookmarks"),
+    ("J", "jobs"),
+    ("m", "set_mark"),
+    ("'", "goto_mark"),
+    ("<lt>", "back"),
+    (">", "forward"),
+    ("t", "new_tab"),
+    ("<C-w>", "close_tab"),
+    ("]", "next_tab"),
+    ("[", "prev_tab"),
+    ("<A-1>", "tab_1"),
+    ("<A-2>", "tab_2"),
+    ("<A-3>", "tab_3"),
+    ("<A-4>", "tab_4"),
+    ("<A-5>", "tab_5"),
+    ("<A-6>", "tab_6"),
+    ("<A-7>", "tab_7"),
+    ("<A-8>", "tab_8"),
+    ("<A-9>", "tab_9"),
+];
+
+/// Name that removes a default binding.
+const UNBOUND: &str = "none";
+
+impl Action {
+    pub fn from_name(name: &str) -> Option<Action> {
+        if let Some(n) = name.strip_prefix("tab_") {
+            return match n.parse::<usize>() {
+                Ok(n @ 1..=9) => Some(Action::Tab(n - 1)),
+                _ => None,
+            };
+        }
+
+        ACTION_NAMES
+            .iter()
+            .find(|(action_name, _)| *action_name == name)
+            .map(|(_, action)| *action)
+    }
+
+    /// Whether the action is followed by a character argument, like the
+    /// letter of `set_mark`.
+    pub fn takes_char(self) -> bool {
+        matches!(self, Action::SetMark | Action::GotoMark)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum KeymapError {
+    InvalidKey { keys: String },
+    UnknownAction { keys: String, action: String },
+    /// Binding `keys` would shadow `other`, which starts with it.
+    Conflict { keys: String, other: String },
+}
+
+impl Display for KeymapError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            KeymapError::InvalidKey { keys } => write!(f, "[keys]: invalid key sequence \"{}\"", keys),
+            KeymapError::UnknownAction { keys, action } => {
+                write!(f, "[keys]: unknown action \"{}\" bound to \"{}\"", action, keys)
+            }
+            KeymapError::Conflict { keys, other } => write!(f,
+                "[keys]: \"{}\" conflicts with \"{}\", bind one of them to \"{}\"",
+                keys, other, UNBOUND),
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+/// What the keys typed so far amount to.
+#[derive(Debug, PartialEq)]
+pub enum Lookup {
+    Action(Action),
+    /// The start of a longer binding.
+    Prefix,
+    None,
+}
+
+/// Key sequences of normal mode and the actions they are bound to.
+#[derive(Debug)]
+pub struct Keymap {
+    bindings: HashMap<Vec<Key>, Action>,
+}
+
+impl Default for Keymap {
+    fn default() -> Keymap {
+        Keymap::new(&BTreeMap::new()).expect("default bindings are valid")
+    }
+}
+
+impl Keymap {
+    /// Builds the keymap from the defaults and the `[keys]` table of the
+    /// config, which maps key sequences to action names.
+    pub fn new(overrides: &BTreeMap<String, String>) -> Result<Keymap, KeymapError> {
+        let mut bindings = HashMap::new();
+        let mut names = HashMap::new();
+
+        let overrides = overrides.iter().map(|(keys, action)| (keys.as_str(), action.as_str()));
+
+        for (text, name) in DEFAULT_BINDINGS.into_iter().chain(overrides) {
+            let keys = parse_keys(text).ok_or_else(|| KeymapError::InvalidKey {
+                keys: text.to_string(),
+            })?;
+
+            if name == UNBOUND {
+                bindings.remove(&keys);
+                continue;
+            }
+            let action = Action::from_name(name).ok_or_else(|| KeymapError::UnknownAction {
+                keys: text.to_string(),
+                action: name.to_string(),
+            })?;
+
+            bindings.insert(keys.clone(), action);
+            names.insert(keys, text.to_string());
+        }
+
+        // without timeouts a binding can't be the start of another one
+        for keys in bindings.keys() {
+            for other in bindings.keys() {
+                if other.len() > keys.len() && other.starts_with(keys) {
+                    return Err(KeymapError::Conflict {
+                        keys: names[keys].clone(),
+                        other: names[other].clone(),
+                    });
+                }
+            }
+        }
+
+        Ok(Keymap { bindings })
+    }
+
+    pub fn lookup(&self, keys: &[Key]) -> Lookup {
+        if let Some(action) = self.bindings.get(keys) {
+            return Lookup::Action(*action);
+        }
+
+        if self.bindings.keys().any(|binding| binding.starts_with(keys)) {
+            Lookup::Prefix
+        } else {
+            Lookup::None
+        }
+    }
+}
+
+/// Parses a key sequence written like in vim: plain characters, with
+/// special keys in angle brackets, e.g. `gg`, `<C-d>` or `<A-1>`.
+/// Digits can't start a sequence, they are typed as counts.
+pub fn parse_keys(text: &str) -> Option<Vec<Key>> {
+    let mut keys = vec![];
+    let mut rest = text;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            let end = rest.find('>')?;
+            keys.push(parse_special(&rest[1..end])?);
+            rest = &rest[end + 1..];
+        } else {
+            keys.push(Key::Char(c));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    match keys.first() {
+        None | Some(Key::Char('0'..='9')) => None,
+        Some(_) => Some(keys),
+    }
+}
+
+fn parse_special(name: &str) -> Option<Key> {
+    let single = |text: &str| {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c),
+            _ => None,
+        }
+    };
+
+    if let Some(c) = name.strip_prefix("C-").and_then(single) {
+        return Some(Key::Ctrl(c));
+    }
+    if let Some(c) = name.strip_prefix("A-").and_then(single) {
+        return Some(Key::Alt(c));
+    }
+
+    let key = match name.to_lowercase().as_str() {
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "home" => Key::Home,
+        "end" => Key::End,
+        "pageup" => Key::PageUp,
+        "pagedown" => Key::PageDown,
+        "del" => Key::Delete,
+        "bs" => Key::Backspace,
+        "esc" => Key::Esc,
+        "cr" | "enter" => Key::Char('\n'),
+        "tab" => Key::Char('\t'),
+        "space" => Key::Char(' '),
+        "lt" => Key::Char('<'),
+        "gt" => Key::Char('>'),
+        _ => return None,
+    };
+
+    Some(key)
+}
\ No newline at end of file
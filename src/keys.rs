@@ -0,0 +1,122 @@
+use std::fmt::{self, Display, Formatter};
+
+use termion::event::Key;
+
+use rufile::keymap::{Action, Keymap, Lookup};
+
+/// A complete key command: the bound action with the count typed before
+/// it and, for actions that take one, the character typed after it.
+#[derive(Debug, PartialEq)]
+pub struct KeyCommand {
+    pub count: Option<usize>,
+    pub action: Action,
+    pub arg: Option<char>,
+}
+
+impl KeyCommand {
+    /// How many times to repeat the command, 1 without a count.
+    pub fn times(&self) -> usize {
+        self.count.unwrap_or(1)
+    }
+}
+
+/// Collects count digits and the keys of multi-key bindings until they
+/// make up a command.
+#[derive(Debug)]
+pub struct PendingKeys {
+    keymap: Keymap,
+    count: Option<usize>,
+    keys: Vec<Key>,
+    /// Action waiting for its character argument.
+    awaiting: Option<Action>,
+}
+
+impl PendingKeys {
+    pub fn new(keymap: Keymap) -> PendingKeys {
+        PendingKeys {
+            keymap,
+            count: None,
+            keys: vec![],
+            awaiting: None,
+        }
+    }
+
+    /// Adds `key` to the keys typed so far, returning the command once it
+    /// is complete. Esc or a key that isn't bound drops them.
+    pub fn push(&mut self, key: Key) -> Option<KeyCommand> {
+        if let Some(action) = self.awaiting {
+            let arg = match key {
+                Key::Char(c) => Some(c),
+                _ => None,
+            };
+            let count = self.count;
+            self.clear();
+
+            return arg.map(|arg| KeyCommand { count, action, arg: Some(arg) });
+        }
+
+        match key {
+            Key::Char(c @ '0'..='9') if self.keys.is_empty() && (c != '0' || self.count.is_some()) => {
+                let digit = c as usize - '0' as usize;
+                self.count = Some(self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+                return None;
+            }
+            Key::Esc if !self.is_empty() => {
+                self.clear();
+                return None;
+            }
+            _ => self.keys.push(key),
+        }
+
+        match self.keymap.lookup(&self.keys) {
+            Lookup::Action(action) if action.takes_char() => {
+                self.awaiting = Some(action);
+                None
+            }
+            Lookup::Action(action) => {
+                let count = self.count;
+                self.clear();
+                Some(KeyCommand { count, action, arg: None })
+            }
+            Lookup::Prefix => None,
+            Lookup::None => {
+                self.clear();
+                None
+            }
+        }
+    }
+
+    /// Whether `key` alone is bound to quitting, for popups that don't
+    /// take other normal mode keys.
+    pub fn is_quit(&self, key: Key) -> bool {
+        self.keymap.lookup(&[key]) == Lookup::Action(Action::Quit)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count.is_none() && self.keys.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.count = None;
+        self.keys.clear();
+        self.awaiting = None;
+    }
+}
+
+impl Display for PendingKeys {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if let Some(count) = self.count {
+            write!(f, "{}", count)?;
+        }
+        for key in &self.keys {
+            match key {
+                Key::Char(c) => write!(f, "{}", c)?,
+                Key::Ctrl(c) => write!(f, "<C-{}>", c)?,
+                Key::Alt(c) => write!(f, "<A-{}>", c)?,
+                _ => write!(f, "<{:?}>", key)?,
+            }
+        }
+
+        Ok(())
+    }
+}
\ No newline at end of file
@@ -1,2 +1,10 @@
+pub mod args;
+pub mod batch;
+pub mod bookmarks;
+pub mod chooser;
+pub mod config;
 pub mod entry;
-pub mod command_input;
\ No newline at end of file
+pub mod command_input;
+pub mod frecency;
+pub mod keymap;
+pub mod search;
\ No newline at end of file
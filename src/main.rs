@@ -1,351 +1,302 @@
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
-use std::{env, error, fs, io};
+use std::path::{Path, PathBuf};
+use std::fs::File;
+use std::io::Write;
+use std::os::fd::{AsFd, AsRawFd};
+use std::{env, error, fs, io, mem, process};
 
 use termion::event::Key;
+use termion::input::MouseTerminal;
 use termion::raw::IntoRawMode;
 use tui::Terminal;
 use termion::screen::IntoAlternateScreen;
 
 use tui::backend::CrosstermBackend;
-use tui::layout::{
-    Constraint,
-    Direction,
-    Layout
-};
-use tui::style::{
-    Color,
-    Modifier,
-    Style
-};
-use tui::text::{
-    Span,
-    Spans
-};
-use tui::widgets::{
-    Block,
-    BorderType,
-    Borders, List,
-    ListItem,
-    ListState,
-    Paragraph
-};
 
-mod entry;
+mod app;
 mod event;
-mod command_input;
-
-use entry::file_data::FileData;
+mod keys;
+mod results;
+mod tab;
+mod ui;
+
+use rufile::args::{Args, USAGE};
+use rufile::batch::{self, Batch, Exec};
+use rufile::bookmarks::Bookmarks;
+use rufile::chooser::{ChooseMode, Chooser};
+use rufile::command_input::input::InputMode;
+use rufile::command_input::path;
+use rufile::config::Config;
+use rufile::entry::file_data::{display_name, path_error};
+use rufile::frecency::Frecency;
+use rufile::keymap::{Action, Keymap};
+use app::App;
 use event::{Event, Events};
-use command_input::input::{CommandHandler, InputMode};
 
 fn main() -> Result<(), Box<dyn error::Error>> {
-    let events: Events = Events::new();
-    let mut command: CommandHandler = CommandHandler::default();
+    let args = match Args::parse(env::args_os().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("rufile: {}\nTry 'rufile --help' for more information.", err);
+            process::exit(2);
+        }
+    };
 
-    let mut path: PathBuf = env::current_dir().unwrap();
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if args.version {
+        println!("rufile {}", env!("CARGO_PKG_VERSION"));
+        return Ok(());
+    }
+
+    let (start_dir, start_file) = match start_location(args.path) {
+        Ok(location) => location,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            process::exit(1);
+        }
+    };
+
+    if !args.exec.is_empty() {
+        process::exit(run_batch(&args.exec, &start_dir, args.read_only));
+    }
+
+    let config = match &args.config {
+        Some(path) => Config::load(path),
+        None => Config::load_default(),
+    };
+    let mut config = match config {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            process::exit(1);
+        }
+    };
+    config.view.show_hidden |= args.show_hidden;
+
+    let keymap = match Keymap::new(&config.keys) {
+        Ok(keymap) => keymap,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let bookmarks = match Bookmarks::load_default() {
+        Ok(bookmarks) => bookmarks,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let frecency = match Frecency::load_default() {
+        Ok(frecency) => frecency,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            process::exit(1);
+        }
+    };
+
+    let events: Events = Events::new();
+    let mut app = App::new(start_dir, &config, keymap, bookmarks, frecency, events.tx.clone());
+    app.tab_mut().reselect(start_file);
+    app.command.read_only = args.read_only;
+    app.chooser = args.choose.as_ref().map(|chooser| chooser.mode);
+
+    // chosen paths going to stdout mustn't mix with the interface
+    let mut chosen_output: Box<dyn Write> = match &args.choose {
+        Some(Chooser { output: None, .. }) => Box::new(stdout_to_tty()?),
+        _ => Box::new(io::sink()),
+    };
 
     let stdout = io::stdout().into_raw_mode()?;
     let stdout = stdout.into_alternate_screen()?;
+    let stdout = MouseTerminal::from(stdout);
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    
-    let mut marked_file = ListState::default();
-    marked_file.select(Some(0));
 
     loop {
-        terminal.draw(|f| {
-            let chunks = Layout::default()
-                .horizontal_margin(1)
-                .direction(Direction::Vertical)
-                .constraints([
-                        Constraint::Min(3),
-                        Constraint::Length(1),
-                    ].as_ref()
-                )
-                .split(f.size());
-            
-            let main_chunks = Layout::default()
-                .direction(Direction::Horizontal)
-                .constraints(
-                    [
-                        Constraint::Percentage(50),
-                        Constraint::Percentage(50),
-                    ].as_ref()
-                )
-                .split(chunks[0]);
-            
-            let right = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints(
-                    [
-                        Constraint::Length(12),
-                        Constraint::Length(6),
-                        Constraint::Length(2)
-                    ].as_ref()
-                )
-                .split(main_chunks[1]);
-
-            let (list, mut paragraphs) = 
-                render_files(&mut marked_file, &path);
-        
-            f.render_stateful_widget(list, main_chunks[0], &mut marked_file);
-            f.render_widget(paragraphs.remove(1), right[1]);
-            f.render_widget(paragraphs.remove(0), right[0]);
-
-            let input_chunk = render_input_field(&command);
-            f.render_widget(input_chunk, chunks[1]);
-
-            match command.input_mode {
-                InputMode::Editing => {
-                    f.set_cursor(
-                        chunks[1].x + command.input.len() as u16,
-                        chunks[1].y,
-                    )
-                }
-                _ => {}
-            }
-        })?;
+        app.update();
+        terminal.draw(|f| ui::draw(f, &mut app))?;
         
         match events.rx.recv()? {
-            Event::Input(input) => match command.input_mode {
-                InputMode::Normal | InputMode::Error => match input {
-                    Key::Char('q') | Key::Ctrl('c') => break,
-                    Key::Up => on_up_pressed(&path, &mut marked_file),
-                    Key::Down => on_down_pressed(&path, &mut marked_file),
-                    Key::Right => on_right_pressed(&mut path, &mut marked_file),
-                    Key::Left => on_left_pressed(&mut path, &mut marked_file),
-                    Key::Char(':') => {
-                        command.input.push(':');
-                        command.input_mode = InputMode::Editing;
+            Event::Input(input) => {
+                // quitting again right after a warning about running jobs
+                let quit_confirmed = mem::take(&mut app.quit_warned);
+                match app.command.input_mode {
+                    InputMode::Normal | InputMode::Error if app.bookmark_list.is_some() => match input {
+                        key if app.keys.is_quit(key) && app.can_quit(quit_confirmed) => break,
+                        Key::Up | Key::Char('k') => app.move_bookmark_selection(false),
+                        Key::Down | Key::Char('j') => app.move_bookmark_selection(true),
+                        Key::Right | Key::Char('\n') => app.goto_selected_bookmark(),
+                        Key::Char('d') => app.delete_selected_bookmark(),
+                        Key::Esc | Key::Char('b') => app.close_bookmarks(),
+                        _ => {}
                     }
-                    _ => {}
-                }
-                InputMode::Editing => match input {
-                    Key::Char('\n') => call_command(&mut command, &path, &marked_file),
-                    Key::Char(c) => command.input.push(c),
-                    Key::Backspace => { command.input.pop(); }
-                    Key::Esc => {
-                        command.input.drain(..);
-                        command.input_mode = InputMode::Normal;
+                    InputMode::Normal | InputMode::Error if app.job_list.is_some() => match input {
+                        key if app.keys.is_quit(key) && app.can_quit(quit_confirmed) => break,
+                        Key::Up | Key::Char('k') => app.move_job_selection(false),
+                        Key::Down | Key::Char('j') => app.move_job_selection(true),
+                        Key::Char(' ') | Key::Char('p') => app.toggle_pause_selected_job(),
+                        Key::Char('x') | Key::Char('d') => app.cancel_selected_job(),
+                        Key::Char('c') => app.clear_finished_jobs(),
+                        Key::Esc | Key::Char('J') => app.close_jobs(),
+                        _ => {}
+                    }
+                    InputMode::Normal | InputMode::Error if app.results.is_some() => match input {
+                        key if app.keys.is_quit(key) && app.can_quit(quit_confirmed) => break,
+                        Key::Up | Key::Char('k') => app.move_results_selection(false),
+                        Key::Down | Key::Char('j') => app.move_results_selection(true),
+                        Key::Right | Key::Char('\n') => app.goto_result(),
+                        Key::Char(':') => {
+                            app.command.input.push(':');
+                            app.command.input_mode = InputMode::Editing;
+                        }
+                        Key::Esc => app.close_results(),
+                        _ => {}
+                    }
+                    InputMode::Normal | InputMode::Error => match app.keys.push(input) {
+                        Some(command) if command.action == Action::Quit && app.can_quit(quit_confirmed) => break,
+                        Some(command) => app.run(command),
+                        None => {}
+                    }
+                    InputMode::Editing => match input {
+                        Key::Char('\n') => app.call_command(),
+                        Key::Char('\t') => app.command.complete(),
+                        Key::Char(c) => app.command.input.push(c),
+                        Key::Backspace => { app.command.input.pop(); }
+                        Key::Esc => {
+                            app.command.input.drain(..);
+                            app.command.input_mode = InputMode::Normal;
+                        }
+                        _ => {}
+                    }
+                    InputMode::Search => match input {
+                        Key::Char('\n') => app.confirm_search(),
+                        Key::Char('\t') => app.cycle_search_kind(),
+                        Key::Char(c) => {
+                            app.command.input.push(c);
+                            app.update_search();
+                        }
+                        Key::Backspace => {
+                            app.command.input.pop();
+                            if app.command.input.is_empty() {
+                                app.clear_filter();
+                            } else {
+                                app.update_search();
+                            }
+                        }
+                        Key::Up => app.tab_mut().on_up_pressed(),
+                        Key::Down => app.tab_mut().on_down_pressed(),
+                        Key::Esc => app.clear_filter(),
+                        _ => {}
+                    }
+                    InputMode::Find => match input {
+                        Key::Char('\n') => app.run_find(),
+                        Key::Char('\t') => app.cycle_find_kind(),
+                        Key::Char(c) => app.command.input.push(c),
+                        Key::Ctrl('a') => app.toggle_find_hidden(),
+                        Key::Up => app.change_find_depth(true),
+                        Key::Down => app.change_find_depth(false),
+                        Key::Backspace => { app.command.input.pop(); }
+                        Key::Esc => app.close_results(),
+                        _ => {}
                     }
-                    _ => {}
                 }
-            },
-            Event::Tick => {},
+            }
+            Event::Mouse(mouse) => app.on_mouse(mouse),
+            Event::Tick => app.refresh(),
+            Event::Job(id, update) => app.on_job_update(id, update),
         }
-    }
 
-    Ok(())
-}
-
-fn render_files<'a>(marked_file: &mut ListState, path: &PathBuf) 
-        -> (List<'a>, Vec<Paragraph<'a>>) {
-    let files = read_dir(&path).unwrap();
-
-    let file_list_view = render_file_list(path, &files);
-    
-    let mut selected_file: Option<&FileData>  = None;
-    
-    if marked_file.selected().is_none() {
-        if files.len() > 0 {
-            marked_file.select(Some(0));
+        if app.quit {
+            break;
         }
     }
-    
-    if let Some(idx) = marked_file.selected() {
-        selected_file = files.get(idx);
-    }
 
-    let mut paragraphs = vec!();
-    paragraphs.push(render_preview(selected_file));
-    paragraphs.push(render_info(selected_file));
+    // don't leave half-copied files behind
+    app.jobs.cancel_all();
 
-    (file_list_view, paragraphs)
-}
+    // leave the alternate screen before printing anything
+    drop(terminal);
 
-fn render_file_list<'a>(path: &PathBuf, files: &Vec<FileData>) -> List<'a> {
-    let title = format!(" {} ", path.to_string_lossy());
-    
-    let files_block = Block::default()
-        .borders(Borders::ALL)
-        .style(Style::default().fg(Color::White))
-        .title(title)
-        .border_style(Style::default().fg(Color::Yellow))
-        .border_type(BorderType::Thick);
-
-    let items: Vec<_> = files
-        .iter()
-        .map(|file| {
-            let mut file_name = file.name.clone();
-            let mut file_color = Style::default();
-            if file.is_dir() {
-                file_name.push('/');
-                file_color = file_color.fg(Color::Blue);
-            }
-            ListItem::new(Spans::from(vec![Span::styled(
-                file_name,
-                file_color,
-            )]))
-        })
-        .collect();
-
-    let file_view = List::new(items)
-        .block(files_block)
-        .highlight_style(
-            Style::default()
-                .bg(Color::Yellow)
-                .fg(Color::Black)
-                .add_modifier(Modifier::BOLD),
-        )
-        .highlight_symbol(">");
-
-    file_view
-}
-
-fn render_preview<'a>(selected_file: Option<&FileData>) -> Paragraph<'a> {
-    let mut preview = String::from("");
-    if let Some(file) = selected_file {
-        if let Ok(text) = file.preview() {
-            preview = text;
-        }
+    if let Some(chooser) = &args.choose {
+        let paths: Vec<&Path> = match chooser.mode {
+            ChooseMode::Files => app.chosen.iter().map(PathBuf::as_path).collect(),
+            ChooseMode::Dir => vec![app.tab().path.as_path()],
+        };
+        chooser.write(&paths, &mut chosen_output)?;
     }
 
-    Paragraph::new(preview)
-        .style(Style::default().fg(Color::White))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::LightBlue))
-                .title(" Preview ")
-                .border_type(BorderType::Thick),
-        )
+    app.frecency.save()?;
+
+    Ok(())
 }
 
-fn render_info<'a>(selected_file: Option<&FileData>) -> Paragraph<'a> {
-    let mut info = String::from("");
-    if let Some(file) = selected_file {
-        info = file.info();
-    }
 
-    Paragraph::new(info)
-        .style(Style::default().fg(Color::White))
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_style(Style::default().fg(Color::Green))
-                .title(" Info ")
-                .border_type(BorderType::Thick),
-        )
-}
+/// Splits the `PATH` argument into the directory to start in and the file
+/// to select there.
+fn start_location(arg: Option<PathBuf>) -> io::Result<(PathBuf, Option<String>)> {
+    let cwd = env::current_dir()?;
+    let target = match arg {
+        Some(arg) => path::normalize(&cwd.join(arg)),
+        None => return Ok((cwd, None)),
+    };
 
-fn render_input_field(command: &CommandHandler) -> Paragraph {
-    let text = match command.input_mode {
-        InputMode::Error => {
-            Spans::from(vec![Span::styled("Invalid command", 
-                        Style::default()
-                        .fg(Color::Red)
-                        .add_modifier(Modifier::REVERSED))
-            ])
-        },
-        _ => Spans::from(command.input.as_ref())
+    let metadata = fs::metadata(&target).map_err(|err| path_error(&target, err))?;
+    let (dir, file) = match (target.parent(), target.file_name()) {
+        (Some(dir), Some(name)) if !metadata.is_dir() => (dir.to_path_buf(), Some(display_name(name))),
+        _ => (target, None),
     };
+    fs::read_dir(&dir).map_err(|err| path_error(&dir, err))?;
 
-    Paragraph::new(text)
-        .style(match command.input_mode {
-            InputMode::Error => Style::default(),
-            _ => Style::default(),
-        })
-        .block(Block::default())
+    Ok((dir, file))
 }
 
-fn read_dir(path: &PathBuf) -> Result<Vec<FileData>, io::Error> {
-    let mut files: Vec<FileData> = Vec::<FileData>::new();
-
-    for entry in fs::read_dir(&path)? {
-        let entry = entry?;
-        
-        if let Ok(entry_data) = FileData::new(entry) {
-            files.push(entry_data);
+/// Runs the commands given with `--exec` and `-c` in `dir`, printing each
+/// one that succeeded. Stops at the first failure and returns the exit
+/// status.
+fn run_batch(exec: &[Exec], dir: &Path, read_only: bool) -> i32 {
+    // scripts are read before changing to `dir`, relative to where we were
+    let lines = match batch::read_lines(exec) {
+        Ok(lines) => lines,
+        Err(err) => {
+            eprintln!("rufile: {}", err);
+            return 1;
         }
+    };
+    if let Err(err) = env::set_current_dir(dir) {
+        eprintln!("rufile: {}", path_error(dir, err));
+        return 1;
     }
 
-    Ok(files)
-}
-
-fn open_file(file_name: &str) {
-    Command::new("xdg-open")
-        .arg(file_name)
-        .stderr(Stdio::null())
-        .spawn().ok();
-}
-
-fn open_dir(path: &mut PathBuf, marked_file: &mut ListState) -> io::Result<()> {
-    let files = read_dir(path)?;
-
-    if files.len() > 0 {
-        marked_file.select(Some(0));
-    } else {
-        marked_file.select(None);
-    }
-
-    env::set_current_dir(&path)?;
-
-    Ok(())
-}
-
-fn on_right_pressed(path: &mut PathBuf, marked_file: &mut ListState) {
-    if let Ok(files) = read_dir(&path) {
-        if let Some(selected) = marked_file.selected() {
-            let file = &files[selected];
-            path.push(&file.name);
-
-            if file.is_file() {
-               open_file(&file.name);
-               path.pop();
-            } else if file.is_dir() {
-               open_dir(path, marked_file).ok();
+    let mut batch = Batch::new(read_only);
+    for (location, line) in lines {
+        match batch.run(&line) {
+            Ok(true) => println!("ok: {}", line.trim()),
+            Ok(false) => {}
+            Err(message) => {
+                eprintln!("rufile: {}: {}: {}", location, line.trim(), message);
+                return 1;
             }
         }
     }
-}
 
-fn on_left_pressed(path: &mut PathBuf, marked_file: &mut ListState) {
-    path.pop();
-    env::set_current_dir(&path).ok();
-    marked_file.select(Some(0));
+    0
 }
 
-fn on_up_pressed(path: &PathBuf, marked_file: &mut ListState) {
-    if let Some(selected) = marked_file.selected() {
-        let files_count = read_dir(&path).unwrap().len(); 
-        if selected > 0 {
-            marked_file.select(Some(selected - 1));
-        } else {
-            marked_file.select(Some(files_count - 1));
-        }
+/// Points stdout at the terminal, so the interface is drawn there while
+/// stdout is captured, e.g. by `$(rufile --choose-dir)`. Returns the
+/// original stdout.
+fn stdout_to_tty() -> io::Result<File> {
+    let stdout = io::stdout().as_fd().try_clone_to_owned()?;
+    let tty = termion::get_tty()?;
+    // SAFETY: both are open file descriptors
+    if unsafe { libc::dup2(tty.as_raw_fd(), libc::STDOUT_FILENO) } < 0 {
+        return Err(io::Error::last_os_error());
     }
-}
 
-fn on_down_pressed(path: &PathBuf, marked_file: &mut ListState) {
-    if let Some(selected) = marked_file.selected() {
-        let files_count = read_dir(&path).unwrap().len(); 
-        if selected >= files_count - 1 {
-            marked_file.select(Some(0));
-        } else {
-            marked_file.select(Some(selected + 1));
-        }
-    }
+    Ok(File::from(stdout))
 }
-
-fn call_command(command: &mut CommandHandler, path: &PathBuf, marked_file: &ListState) {
-    match marked_file.selected() {
-        Some(selected) => {
-            let files = read_dir(&path);
-            if let Ok(files) = files {
-                command.exec(Some(&files[selected].name));
-            } 
-        }
-        None => command.exec(None) 
-    }
-}
\ No newline at end of file
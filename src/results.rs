@@ -0,0 +1,157 @@
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+use regex::Regex;
+use tui::widgets::ListState;
+
+use rufile::search::finder::{FindResult, Finder};
+use rufile::search::grep::{self, Grep, GrepHit};
+use rufile::search::matcher::MatchKind;
+
+/// Results of a recursive find, filled in while the walk is running.
+pub struct FindView {
+    pub finder: Finder,
+    pub pattern: String,
+    pub kind: MatchKind,
+    pub results: Vec<FindResult>,
+}
+
+/// Lines matched by a content search.
+pub struct GrepView {
+    pub grep: Grep,
+    pub regex: Regex,
+    /// Whether hidden files were searched too.
+    pub show_hidden: bool,
+    pub hits: Vec<GrepHit>,
+}
+
+pub enum ResultKind {
+    Find(FindView),
+    Grep(GrepView),
+}
+
+/// Lines around a grep hit, shown in the preview.
+pub type Context = Vec<(usize, String)>;
+
+/// A list of search results shown in place of the directory listing.
+pub struct Results {
+    pub kind: ResultKind,
+    pub selected: ListState,
+    /// Context of the selected hit with its path and line, read again only
+    /// when another hit is selected.
+    context: Option<(PathBuf, usize, Context)>,
+}
+
+impl Results {
+    pub fn new(kind: ResultKind) -> Results {
+        Results {
+            kind,
+            selected: ListState::default(),
+            context: None,
+        }
+    }
+
+    /// Collects what the background search found since the last call.
+    pub fn poll(&mut self) {
+        let selected = self.selected.selected();
+        let (added, before_selected) = match &mut self.kind {
+            ResultKind::Find(find) => {
+                let found = find.finder.poll();
+                // the selection stays on its result, which new ones with
+                // better scores are sorted in front of
+                let before_selected = match selected.and_then(|idx| find.results.get(idx)) {
+                    Some(current) => found.iter().filter(|result| result.score > current.score).count(),
+                    None => 0,
+                };
+                let added = found.len();
+                find.results.extend(found);
+                // best scores first, ties in the order they were found; the
+                // sort is stable and cheap on the already sorted part
+                find.results.sort_by_key(|result| Reverse(result.score));
+                (added, before_selected)
+            }
+            ResultKind::Grep(grep) => {
+                let hits = grep.grep.poll();
+                let added = hits.len();
+                grep.hits.extend(hits);
+                (added, 0)
+            }
+        };
+
+        match selected {
+            Some(selected) => self.selected.select(Some(selected + before_selected)),
+            None if added > 0 => self.selected.select(Some(0)),
+            None => {}
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match &self.kind {
+            ResultKind::Find(find) => find.results.len(),
+            ResultKind::Grep(grep) => grep.hits.len(),
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        match &self.kind {
+            ResultKind::Find(find) => find.finder.is_done(),
+            ResultKind::Grep(grep) => grep.grep.is_done(),
+        }
+    }
+
+    pub fn move_selection(&mut self, down: bool) {
+        let count = self.len();
+        if let Some(selected) = self.selected.selected() {
+            let next = if down {
+                (selected + 1) % count
+            } else {
+                (selected + count - 1) % count
+            };
+            self.selected.select(Some(next));
+        }
+    }
+
+    /// Absolute path of the selected result.
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        let idx = self.selected.selected()?;
+        match &self.kind {
+            ResultKind::Find(find) => find
+                .results
+                .get(idx)
+                .map(|result| find.finder.root().join(&result.path)),
+            ResultKind::Grep(grep) => grep
+                .hits
+                .get(idx)
+                .map(|hit| grep.grep.root().join(&hit.path)),
+        }
+    }
+
+    /// Reads the context of the selected hit if it isn't cached yet.
+    pub fn update_context(&mut self) {
+        let (path, line) = match (self.selected_path(), self.selected_hit()) {
+            (Some(path), Some(hit)) => (path, hit.line),
+            _ => {
+                self.context = None;
+                return;
+            }
+        };
+        if matches!(&self.context, Some((cached, cached_line, _)) if *cached == path && *cached_line == line) {
+            return;
+        }
+
+        let context = grep::context(&path, line, 4).unwrap_or_default();
+        self.context = Some((path, line, context));
+    }
+
+    /// Context of the selected hit, as of the last `update_context`.
+    pub fn context(&self) -> Option<&Context> {
+        self.context.as_ref().map(|(_, _, context)| context)
+    }
+
+    pub fn selected_hit(&self) -> Option<&GrepHit> {
+        match &self.kind {
+            ResultKind::Grep(grep) => grep.hits.get(self.selected.selected()?),
+            _ => None,
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use crate::entry::file_data::FileData;
+use crate::entry::visibility::Visibility;
+
+use super::matcher::Matcher;
+
+/// An entry of a filtered listing: its index in the full listing and the
+/// positions of the matched characters in its name.
+#[derive(Debug)]
+pub struct ViewEntry {
+    pub idx: usize,
+    pub positions: Vec<usize>,
+}
+
+/// Keeps the entries of `files` that are not hidden and whose names match,
+/// in listing order. Without a matcher every visible entry is kept.
+/// Also returns how many entries were hidden.
+pub fn filter_files(
+    files: &[FileData],
+    dir: &Path,
+    visibility: &Visibility,
+    matcher: Option<&Matcher>,
+) -> (Vec<ViewEntry>, usize) {
+    let mut hidden = 0;
+    let mut entries = vec![];
+
+    for (idx, file) in files.iter().enumerate() {
+        if visibility.is_hidden(dir, file) {
+            hidden += 1;
+            continue;
+        }
+
+        match matcher {
+            Some(matcher) => {
+                if let Some(m) = matcher.matches(&file.name) {
+                    entries.push(ViewEntry { idx, positions: m.positions });
+                }
+            }
+            None => entries.push(ViewEntry { idx, positions: vec![] }),
+        }
+    }
+
+    (entries, hidden)
+}
\ No newline at end of file
@@ -0,0 +1,100 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::job::SearchJob;
+use super::matcher::Matcher;
+
+#[derive(Clone, Copy, Debug)]
+pub struct WalkOptions {
+    pub max_depth: usize,
+    pub show_hidden: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> WalkOptions {
+        WalkOptions {
+            max_depth: 8,
+            show_hidden: false,
+        }
+    }
+}
+
+/// Walks the tree below `root` depth first, calling `visit` with the path
+/// of every entry and whether it is a directory. Symbolic links are not
+/// followed. Stops early once `cancel` is set.
+pub fn walk<F>(root: &Path, options: WalkOptions, cancel: &AtomicBool, mut visit: F)
+where
+    F: FnMut(&Path, bool),
+{
+    let mut stack: Vec<(PathBuf, usize)> = vec![(root.to_path_buf(), 1)];
+
+    while let Some((dir, depth)) = stack.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        let mut subdirs = vec![];
+        for entry in entries.flatten() {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if hidden && !options.show_hidden {
+                continue;
+            }
+
+            let is_dir = entry.file_type().is_ok_and(|ty| ty.is_dir());
+            let path = entry.path();
+            visit(&path, is_dir);
+
+            if is_dir && depth < options.max_depth {
+                subdirs.push((path, depth + 1));
+            }
+        }
+
+        // keep the walk in listing order
+        subdirs.reverse();
+        stack.extend(subdirs);
+    }
+}
+
+#[derive(Debug)]
+pub struct FindResult {
+    /// Path relative to the search root.
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub score: i64,
+    /// Matched char positions within the file name.
+    pub positions: Vec<usize>,
+}
+
+/// Finds entries whose names match in a background thread.
+pub type Finder = SearchJob<FindResult>;
+
+impl SearchJob<FindResult> {
+    pub fn spawn(root: PathBuf, matcher: Matcher, options: WalkOptions) -> Finder {
+        SearchJob::start(root, move |root, cancel, tx| {
+            walk(root, options, cancel, |path, is_dir| {
+                let name = match path.file_name() {
+                    Some(name) => name.to_string_lossy(),
+                    None => return,
+                };
+
+                if let Some(found) = matcher.matches(&name) {
+                    let result = FindResult {
+                        path: path.strip_prefix(root).unwrap_or(path).to_path_buf(),
+                        is_dir,
+                        score: found.score,
+                        positions: found.positions,
+                    };
+                    if tx.send(result).is_err() {
+                        cancel.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        })
+    }
+}
\ No newline at end of file
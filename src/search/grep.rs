@@ -0,0 +1,127 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use filemagic::{Flags, Magic};
+use regex::{Regex, RegexBuilder};
+
+use super::finder::{walk, WalkOptions};
+use super::job::SearchJob;
+
+#[derive(Debug)]
+pub struct GrepHit {
+    /// Path relative to the search root.
+    pub path: PathBuf,
+    /// 1-based line number.
+    pub line: usize,
+    pub text: String,
+    /// Byte ranges of the matches within `text`.
+    pub matches: Vec<Range<usize>>,
+}
+
+/// Searches file contents in a background thread.
+pub type Grep = SearchJob<GrepHit>;
+
+pub fn build_regex(pattern: &str, ignore_case: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+}
+
+impl SearchJob<GrepHit> {
+    pub fn spawn(root: PathBuf, regex: Regex, options: WalkOptions) -> Grep {
+        SearchJob::start(root, move |root, cancel, tx| {
+            let magic = Magic::open(Flags::MIME_ENCODING)
+                .and_then(|magic| magic.load::<&str>(&[]).map(|_| magic))
+                .ok();
+
+            walk(root, options, cancel, |path, is_dir| {
+                if is_dir || is_binary(magic.as_ref(), path) {
+                    return;
+                }
+
+                let relative = path.strip_prefix(root).unwrap_or(path);
+                let sent = search_file(&regex, path, |line, text, matches| {
+                    let hit = GrepHit {
+                        path: relative.to_path_buf(),
+                        line,
+                        text,
+                        matches,
+                    };
+                    tx.send(hit).is_ok() && !cancel.load(Ordering::Relaxed)
+                });
+
+                if let Ok(false) = sent {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            });
+        })
+    }
+}
+
+/// Calls `hit` for every matching line of the file at `path` until it
+/// returns `false`. Returns whether the whole file was searched.
+pub fn search_file<F>(regex: &Regex, path: &Path, mut hit: F) -> io::Result<bool>
+where
+    F: FnMut(usize, String, Vec<Range<usize>>) -> bool,
+{
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = vec![];
+    let mut line = 0;
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            return Ok(true);
+        }
+        line += 1;
+
+        let text = String::from_utf8_lossy(&buffer);
+        let text = text.trim_end_matches(['\n', '\r']);
+        let matches: Vec<_> = regex.find_iter(text).map(|m| m.range()).collect();
+
+        if !matches.is_empty() && !hit(line, text.to_string(), matches) {
+            return Ok(false);
+        }
+    }
+}
+
+/// Reads the lines within `radius` of the 1-based `line`, paired with
+/// their line numbers.
+pub fn context(path: &Path, line: usize, radius: usize) -> io::Result<Vec<(usize, String)>> {
+    let first = line.saturating_sub(radius).max(1);
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut buffer = vec![];
+    let mut lines = vec![];
+
+    for number in 1..=line + radius {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        if number >= first {
+            let text = String::from_utf8_lossy(&buffer);
+            lines.push((number, text.trim_end_matches(['\n', '\r']).to_string()));
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Uses the MIME encoding reported by libmagic, falling back to looking
+/// for NUL bytes when the magic database is not available.
+fn is_binary(magic: Option<&Magic>, path: &Path) -> bool {
+    if let Some(magic) = magic {
+        if let Ok(encoding) = magic.file(path) {
+            return encoding == "binary";
+        }
+    }
+
+    let mut head = [0; 1024];
+    match File::open(path).and_then(|mut file| file.read(&mut head)) {
+        Ok(len) => head[..len].contains(&0),
+        Err(_) => true,
+    }
+}
\ No newline at end of file
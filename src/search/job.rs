@@ -0,0 +1,72 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+/// A search running in a background thread below `root`. Items are
+/// collected with `poll`; dropping the job cancels it.
+pub struct SearchJob<T> {
+    root: PathBuf,
+    rx: Receiver<T>,
+    cancel: Arc<AtomicBool>,
+    done: bool,
+}
+
+impl<T: Send + 'static> SearchJob<T> {
+    /// Runs `work` on a new thread. It should stop once the cancel flag is
+    /// set; the job is done when `work` returns.
+    pub fn start<F>(root: PathBuf, work: F) -> SearchJob<T>
+    where
+        F: FnOnce(&Path, &AtomicBool, Sender<T>) + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let work_root = root.clone();
+        let work_cancel = Arc::clone(&cancel);
+        thread::spawn(move || work(&work_root, &work_cancel, tx));
+
+        SearchJob {
+            root,
+            rx,
+            cancel,
+            done: false,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the items found since the last call.
+    pub fn poll(&mut self) -> Vec<T> {
+        let mut items = vec![];
+        loop {
+            match self.rx.try_recv() {
+                Ok(item) => items.push(item),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+
+        items
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl<T> Drop for SearchJob<T> {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
\ No newline at end of file
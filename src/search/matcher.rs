@@ -0,0 +1,299 @@
+use std::fmt::{self, Display, Formatter};
+
+use regex::{Regex, RegexBuilder};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MatchKind {
+    Substring,
+    Glob,
+    Regex,
+    Fuzzy,
+}
+
+impl MatchKind {
+    pub fn next(self) -> MatchKind {
+        match self {
+            MatchKind::Substring => MatchKind::Glob,
+            MatchKind::Glob => MatchKind::Regex,
+            MatchKind::Regex => MatchKind::Fuzzy,
+            MatchKind::Fuzzy => MatchKind::Substring,
+        }
+    }
+}
+
+impl Display for MatchKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            MatchKind::Substring => "substring",
+            MatchKind::Glob => "glob",
+            MatchKind::Regex => "regex",
+            MatchKind::Fuzzy => "fuzzy",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// A successful match. `positions` holds the char indices of the matched
+/// characters in the text, used to highlight them.
+#[derive(Debug, PartialEq)]
+pub struct Match {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+/// Matches file names against a pattern. Matching is smart-case: it only
+/// respects case when the pattern contains an uppercase character.
+#[derive(Clone, Debug)]
+pub struct Matcher {
+    kind: MatchKind,
+    source: String,
+    pattern: Vec<char>,
+    regex: Option<Regex>,
+    ignore_case: bool,
+}
+
+impl Matcher {
+    /// Fails only for `MatchKind::Regex` patterns that don't compile.
+    pub fn new(kind: MatchKind, pattern: &str) -> Result<Matcher, regex::Error> {
+        let ignore_case = !pattern.chars().any(char::is_uppercase);
+        let regex = match kind {
+            MatchKind::Regex => Some(
+                RegexBuilder::new(pattern)
+                    .case_insensitive(ignore_case)
+                    .build()?,
+            ),
+            _ => None,
+        };
+        let chars = pattern
+            .chars()
+            .map(|c| if ignore_case { fold(c) } else { c })
+            .collect();
+
+        Ok(Matcher {
+            kind,
+            source: pattern.to_string(),
+            pattern: chars,
+            regex,
+            ignore_case,
+        })
+    }
+
+    pub fn kind(&self) -> MatchKind {
+        self.kind
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.source
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pattern.is_empty()
+    }
+
+    pub fn matches(&self, text: &str) -> Option<Match> {
+        if let Some(regex) = &self.regex {
+            return regex_match(regex, text);
+        }
+
+        let text: Vec<char> = text
+            .chars()
+            .map(|c| if self.ignore_case { fold(c) } else { c })
+            .collect();
+
+        if self.pattern.is_empty() {
+            return Some(Match {
+                score: 0,
+                positions: vec![],
+            });
+        }
+
+        match self.kind {
+            MatchKind::Substring => substring_match(&self.pattern, &text),
+            MatchKind::Glob => glob_match(&self.pattern, &text)
+                .map(|positions| Match { score: 0, positions }),
+            MatchKind::Fuzzy => fuzzy_match(&self.pattern, &text),
+            MatchKind::Regex => None,
+        }
+    }
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn substring_match(pattern: &[char], text: &[char]) -> Option<Match> {
+    let start = text
+        .windows(pattern.len())
+        .position(|window| window == pattern)?;
+
+    Some(Match {
+        // earlier occurrences rank higher
+        score: -(start as i64),
+        positions: (start..start + pattern.len()).collect(),
+    })
+}
+
+fn regex_match(regex: &Regex, text: &str) -> Option<Match> {
+    let found = regex.find(text)?;
+    let start = text[..found.start()].chars().count();
+    let len = found.as_str().chars().count();
+
+    Some(Match {
+        score: -(start as i64),
+        positions: (start..start + len).collect(),
+    })
+}
+
+/// Matches the whole text against a shell glob supporting `*`, `?` and
+/// bracket classes (`[abc]`, `[a-z]`, `[!a]`), returning the positions of
+/// the chars matched by anything but `*`. On a mismatch only the last `*`
+/// takes one more char, so patterns with many stars don't backtrack
+/// exponentially.
+fn glob_match(pattern: &[char], text: &[char]) -> Option<Vec<usize>> {
+    let (mut p, mut t) = (0, 0);
+    let mut positions = vec![];
+    // pattern index after the last `*`, the text index it resumes from
+    // and the positions matched before it
+    let mut star: Option<(usize, usize, usize)> = None;
+
+    while t < text.len() {
+        if pattern.get(p) == Some(&'*') {
+            p += 1;
+            star = Some((p, t, positions.len()));
+            continue;
+        }
+        if let Some(next) = match_one(pattern, p, text[t]) {
+            positions.push(t);
+            p = next;
+            t += 1;
+            continue;
+        }
+
+        let (star_p, star_t, len) = star?;
+        positions.truncate(len);
+        p = star_p;
+        t = star_t + 1;
+        star = Some((star_p, t, len));
+    }
+
+    pattern[p..].iter().all(|&c| c == '*').then_some(positions)
+}
+
+/// Whether the token at `pattern[p]` matches `c`, returning the index of
+/// the token after it.
+fn match_one(pattern: &[char], p: usize, c: char) -> Option<usize> {
+    let &token = pattern.get(p)?;
+    match token {
+        '?' => Some(p + 1),
+        '[' => match parse_class(&pattern[p + 1..]) {
+            Some((class, negated, after)) => {
+                let in_class = class.iter().any(|&(lo, hi)| lo <= c && c <= hi);
+                (in_class != negated).then_some(pattern.len() - after.len())
+            }
+            // an unterminated class matches a literal '['
+            None => (c == '[').then_some(p + 1),
+        },
+        _ => (c == token).then_some(p + 1),
+    }
+}
+
+type CharClass = Vec<(char, char)>;
+
+fn parse_class(pattern: &[char]) -> Option<(CharClass, bool, &[char])> {
+    let mut idx = 0;
+    let negated = matches!(pattern.first(), Some('!') | Some('^'));
+    if negated {
+        idx += 1;
+    }
+
+    let mut class = vec![];
+    let start = idx;
+    while idx < pattern.len() {
+        let c = pattern[idx];
+        if c == ']' && idx > start {
+            return Some((class, negated, &pattern[idx + 1..]));
+        }
+        if idx + 2 < pattern.len() && pattern[idx + 1] == '-' && pattern[idx + 2] != ']' {
+            class.push((c, pattern[idx + 2]));
+            idx += 3;
+        } else {
+            class.push((c, c));
+            idx += 1;
+        }
+    }
+
+    None
+}
+
+const SCORE_MATCH: i64 = 16;
+const BONUS_CONSECUTIVE: i64 = 8;
+const BONUS_BOUNDARY: i64 = 8;
+const BONUS_FIRST_CHAR: i64 = 8;
+const PENALTY_GAP: i64 = 1;
+
+/// Subsequence match: every pattern character has to appear in order.
+/// The shortest window ending at the first complete match is used, so
+/// "fb" in "foo_bar" highlights "f" and "b" rather than "o" and "b".
+fn fuzzy_match(pattern: &[char], text: &[char]) -> Option<Match> {
+    // forward pass: find where the first complete match ends
+    let mut p = 0;
+    let mut end = 0;
+    for (idx, &c) in text.iter().enumerate() {
+        if c == pattern[p] {
+            p += 1;
+            if p == pattern.len() {
+                end = idx;
+                break;
+            }
+        }
+    }
+    if p < pattern.len() {
+        return None;
+    }
+
+    // backward pass: tighten the window from its end
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut p = pattern.len();
+    for idx in (0..=end).rev() {
+        if text[idx] == pattern[p - 1] {
+            positions.push(idx);
+            p -= 1;
+            if p == 0 {
+                break;
+            }
+        }
+    }
+    positions.reverse();
+
+    let mut score = 0;
+    for (i, &pos) in positions.iter().enumerate() {
+        score += SCORE_MATCH;
+        if pos == 0 {
+            score += BONUS_FIRST_CHAR;
+        }
+        if is_boundary(text, pos) {
+            score += BONUS_BOUNDARY;
+        }
+        if i > 0 {
+            let gap = pos - positions[i - 1] - 1;
+            if gap == 0 {
+                score += BONUS_CONSECUTIVE;
+            } else {
+                score -= PENALTY_GAP * gap as i64;
+            }
+        }
+    }
+
+    Some(Match { score, positions })
+}
+
+fn is_boundary(text: &[char], pos: usize) -> bool {
+    if pos == 0 {
+        return true;
+    }
+
+    let prev = text[pos - 1];
+    let curr = text[pos];
+    matches!(prev, '_' | '-' | '.' | ' ' | '/') || (prev.is_lowercase() && curr.is_uppercase())
+}
\ No newline at end of file
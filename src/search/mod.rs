@@ -0,0 +1,5 @@
+pub mod filter;
+pub mod finder;
+pub mod grep;
+pub mod job;
+pub mod matcher;
\ No newline at end of file
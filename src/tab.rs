@@ -0,0 +1,838 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ffi::OsStr;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::{env, io};
+
+use tui::layout::Rect;
+use tui::widgets::ListState;
+
+use rufile::config::ViewConfig;
+use rufile::entry::file_data::{display_name, path_error, FileData};
+use rufile::entry::listing::DirListing;
+use rufile::entry::sort::SortOrder;
+use rufile::entry::tree::{Tree, TreeRow};
+use rufile::entry::visibility::Visibility;
+use rufile::search::filter::{filter_files, ViewEntry};
+use rufile::search::matcher::{MatchKind, Matcher};
+
+/// A directory being browsed, with its own selection, sort and filter.
+pub struct Tab {
+    pub path: PathBuf,
+    pub files: DirListing,
+    pub marked_file: ListState,
+    /// First row shown, mirroring the offset `List` keeps in `marked_file`.
+    scroll: usize,
+    /// Rows that fit in the file list, as of the last draw.
+    page_height: usize,
+    /// Where the file list was last drawn, for mouse clicks.
+    pub list_area: Rect,
+    /// Entries of `files` left after filtering.
+    view: Vec<ViewEntry>,
+    /// Lines currently shown: `view` with the children of expanded
+    /// directories; indexed by `marked_file`.
+    rows: Vec<TreeRow>,
+    /// Whether directories can be expanded inline.
+    pub tree_view: bool,
+    tree: Tree,
+    pub visibility: Visibility,
+    /// Entries of `files` left out by `visibility`.
+    pub hidden_count: usize,
+    pub filter: Option<Matcher>,
+    last_search: Option<Matcher>,
+    /// Directories left, most recent last, for going back.
+    back: Vec<PathBuf>,
+    /// Directories gone back from, most recent last.
+    forward: Vec<PathBuf>,
+    /// Name of the entry last selected in each visited directory.
+    selections: HashMap<PathBuf, String>,
+    /// Entries selected for operations, by path. They stay selected while
+    /// other directories are browsed.
+    pub marked: BTreeSet<PathBuf>,
+    /// Row where the visual selection started, the range up to the cursor
+    /// is selected when it ends.
+    visual_start: Option<usize>,
+    /// Whether the parent and preview columns are kept up to date.
+    pub columns: bool,
+    /// Listing of the parent directory, shown in the Miller layout.
+    pub parent: DirListing,
+    /// Listing of the selected directory, shown in the Miller layout.
+    pub preview_dir: DirListing,
+    /// Selected directory that couldn't be read, not retried until the
+    /// selection moves.
+    preview_failed: Option<PathBuf>,
+}
+
+impl Tab {
+    pub fn new(path: PathBuf, config: &ViewConfig) -> Tab {
+        let mut tab = Tab {
+            path,
+            files: DirListing::default(),
+            marked_file: ListState::default(),
+            scroll: 0,
+            page_height: 0,
+            list_area: Rect::default(),
+            view: vec![],
+            rows: vec![],
+            tree_view: false,
+            tree: Tree::default(),
+            visibility: Visibility::new(config),
+            hidden_count: 0,
+            filter: None,
+            last_search: None,
+            back: vec![],
+            forward: vec![],
+            selections: HashMap::new(),
+            marked: BTreeSet::new(),
+            visual_start: None,
+            columns: false,
+            parent: DirListing::default(),
+            preview_dir: DirListing::default(),
+            preview_failed: None,
+        };
+        tab.open_dir().ok();
+
+        tab
+    }
+
+    /// Opens the same directory with the same settings and selection.
+    pub fn duplicate(&self) -> Tab {
+        let mut tab = Tab::new(self.path.clone(), &ViewConfig::default());
+        tab.visibility = self.visibility.clone();
+        tab.tree_view = self.tree_view;
+        tab.set_sort(self.files.sort_order());
+        tab.set_columns(self.columns);
+        tab.reselect(self.selected_file().map(|file| file.name.clone()));
+
+        tab
+    }
+
+    /// Short name shown in the tab bar.
+    pub fn title(&self) -> String {
+        match self.path.file_name() {
+            Some(name) => display_name(name),
+            None => display_name(self.path.as_os_str()),
+        }
+    }
+
+    /// Picks up changes reported by the directory watches. If the current
+    /// directory can't be read anymore, e.g. because it was deleted, the
+    /// nearest readable ancestor is opened instead.
+    pub fn refresh(&mut self) -> io::Result<()> {
+        let selected = self.selected_file().map(|file| file.name.clone());
+        match self.files.refresh() {
+            Ok(true) => {
+                self.prune_marked();
+                self.rebuild_view(selected);
+            }
+            Ok(false) => {}
+            Err(err) => {
+                let err = path_error(&self.path, err);
+                self.fall_back();
+                return Err(err);
+            }
+        }
+
+        if self.columns {
+            self.parent.refresh().ok();
+            self.preview_dir.refresh().ok();
+        }
+
+        Ok(())
+    }
+
+    /// Reads the current directory again right away, e.g. after an
+    /// operation changed it.
+    pub fn reload(&mut self) -> io::Result<()> {
+        self.tree.reload(&self.path, self.files.sort_order());
+        self.files.invalidate();
+        // its permissions may have changed
+        self.preview_failed = None;
+        self.refresh()
+    }
+
+    /// Opens the nearest ancestor of the current directory that can be read.
+    fn fall_back(&mut self) {
+        let ancestors: Vec<PathBuf> = self.path
+            .ancestors()
+            .skip(1)
+            .map(Path::to_path_buf)
+            .collect();
+
+        for dir in ancestors {
+            if self.open(dir).is_ok() {
+                return;
+            }
+        }
+    }
+
+    pub fn set_columns(&mut self, columns: bool) {
+        self.columns = columns;
+        self.load_parent();
+    }
+
+    /// Loads the listing of the selected directory for the preview column.
+    /// It is cached until the selection moves to another directory.
+    pub fn update_preview(&mut self) {
+        if !self.columns {
+            return;
+        }
+
+        let dir = match self.selected_file() {
+            Some(file) if file.is_dir() => self.path.join(file.raw_name()),
+            _ => {
+                self.preview_failed = None;
+                return;
+            }
+        };
+        if self.preview_failed.as_ref() == Some(&dir) {
+            return;
+        }
+        self.preview_failed = None;
+
+        if self.preview_dir.path() != dir {
+            self.preview_dir.set_sort(self.files.sort_order());
+            if self.preview_dir.load(&dir).is_err() {
+                self.preview_dir = DirListing::default();
+                self.preview_failed = Some(dir);
+            }
+        }
+    }
+
+    /// Entries of the selected directory, or `None` if a file is selected.
+    pub fn preview_entries(&self) -> Option<Vec<&FileData>> {
+        match self.selected_file() {
+            Some(file) if file.is_dir() && self.preview_dir.path() == self.path.join(file.raw_name()) => {
+                Some(self.visible_entries(&self.preview_dir))
+            }
+            _ => None,
+        }
+    }
+
+    /// Entries of the parent directory and the position of the current one.
+    pub fn parent_entries(&self) -> (Vec<&FileData>, Option<usize>) {
+        let entries = self.visible_entries(&self.parent);
+        let current = self.path.file_name().map(display_name);
+        let position = entries
+            .iter()
+            .position(|file| Some(file.name.as_str()) == current.as_deref());
+
+        (entries, position)
+    }
+
+    fn visible_entries<'a>(&self, listing: &'a DirListing) -> Vec<&'a FileData> {
+        listing
+            .files()
+            .iter()
+            .filter(|file| !self.visibility.is_hidden(listing.path(), file))
+            .collect()
+    }
+
+    fn load_parent(&mut self) {
+        if !self.columns {
+            return;
+        }
+
+        match self.path.parent() {
+            Some(parent) => {
+                self.parent.set_sort(self.files.sort_order());
+                if self.parent.load(parent).is_err() {
+                    self.parent = DirListing::default();
+                }
+            }
+            None => self.parent = DirListing::default(),
+        }
+    }
+
+    /// Lines to render, with the positions of the characters matched by
+    /// the active filter.
+    pub fn visible_files(&self) -> impl Iterator<Item = (&TreeRow, &FileData, &[usize])> {
+        self.rows.iter().filter_map(|row| {
+            let positions = match row.parent {
+                None => self.view.get(row.idx)?.positions.as_slice(),
+                Some(_) => &[],
+            };
+            Some((row, self.row_file(row)?, positions))
+        })
+    }
+
+    fn row_file(&self, row: &TreeRow) -> Option<&FileData> {
+        match &row.parent {
+            None => self.files.get(self.view.get(row.idx)?.idx),
+            Some(parent) => self.tree.children(parent).get(row.idx),
+        }
+    }
+
+    fn row_path(&self, row: &TreeRow) -> Option<PathBuf> {
+        self.row_file(row).map(|file| self.path.join(file.raw_name()))
+    }
+
+    /// Whether the entry on row `idx` is selected or in the visual range.
+    pub fn is_marked(&self, idx: usize) -> bool {
+        if self.visual_range().is_some_and(|range| range.contains(&idx)) {
+            return true;
+        }
+
+        self.rows
+            .get(idx)
+            .and_then(|row| self.row_path(row))
+            .is_some_and(|path| self.marked.contains(&path))
+    }
+
+    pub fn is_visual(&self) -> bool {
+        self.visual_start.is_some()
+    }
+
+    fn visual_range(&self) -> Option<RangeInclusive<usize>> {
+        let start = self.visual_start?;
+        let cursor = self.marked_file.selected()?;
+
+        Some(start.min(cursor)..=start.max(cursor))
+    }
+
+    /// Selects or unselects `count` entries from the cursor on and moves
+    /// the cursor past them.
+    pub fn toggle_select(&mut self, count: usize) {
+        let start = match self.marked_file.selected() {
+            Some(start) => start,
+            None => return,
+        };
+
+        let end = start.saturating_add(count).min(self.rows.len());
+        for idx in start..end {
+            self.toggle_row(idx);
+        }
+        self.select_row(end);
+    }
+
+    fn toggle_row(&mut self, idx: usize) {
+        if let Some(path) = self.rows.get(idx).and_then(|row| self.row_path(row)) {
+            if !self.marked.remove(&path) {
+                self.marked.insert(path);
+            }
+        }
+    }
+
+    /// Starts a visual selection at the cursor, or ends it selecting the
+    /// entries in its range.
+    pub fn toggle_visual(&mut self) {
+        if self.visual_start.is_some() {
+            self.commit_visual();
+        } else {
+            self.visual_start = self.marked_file.selected();
+        }
+    }
+
+    /// Ends the visual selection without selecting anything. Returns
+    /// whether there was one.
+    pub fn cancel_visual(&mut self) -> bool {
+        self.visual_start.take().is_some()
+    }
+
+    fn commit_visual(&mut self) {
+        if let Some(range) = self.visual_range() {
+            let paths: Vec<_> = range
+                .filter_map(|idx| self.rows.get(idx))
+                .filter_map(|row| self.row_path(row))
+                .collect();
+            self.marked.extend(paths);
+        }
+        self.visual_start = None;
+    }
+
+    pub fn select_all(&mut self) {
+        self.visual_start = None;
+        let paths: Vec<_> = self.rows.iter().filter_map(|row| self.row_path(row)).collect();
+        self.marked.extend(paths);
+    }
+
+    /// Selects the shown entries that aren't selected and unselects those
+    /// that are.
+    pub fn invert_selection(&mut self) {
+        self.commit_visual();
+        for idx in 0..self.rows.len() {
+            self.toggle_row(idx);
+        }
+    }
+
+    /// Unselects everything, also in other directories.
+    pub fn clear_selection(&mut self) {
+        self.visual_start = None;
+        self.marked.clear();
+    }
+
+    /// Selects the shown entries whose names match the glob `pattern`.
+    /// Returns how many did.
+    pub fn select_glob(&mut self, pattern: &str) -> usize {
+        let matcher = match Matcher::new(MatchKind::Glob, pattern) {
+            Ok(matcher) => matcher,
+            Err(_) => return 0,
+        };
+
+        let paths: Vec<_> = self.rows
+            .iter()
+            .filter_map(|row| self.row_file(row))
+            .filter(|file| {
+                // nested entries are named by their path below this directory
+                let name = file.name.rsplit('/').next().unwrap_or(&file.name);
+                matcher.matches(name).is_some()
+            })
+            .map(|file| self.path.join(file.raw_name()))
+            .collect();
+
+        let count = paths.len();
+        self.marked.extend(paths);
+        count
+    }
+
+    /// The selected entries, ending a visual selection first.
+    pub fn marked_paths(&mut self) -> Vec<PathBuf> {
+        self.commit_visual();
+        self.marked.iter().cloned().collect()
+    }
+
+    /// Forgets selected entries that were deleted or moved away.
+    fn prune_marked(&mut self) {
+        self.marked.retain(|path| path.symlink_metadata().is_ok());
+    }
+
+    pub fn toggle_tree_view(&mut self) {
+        self.tree_view = !self.tree_view;
+        if !self.tree_view {
+            self.tree.clear();
+        }
+
+        // a nested entry is gone with the tree, keep its top-level directory
+        let selected = self.selected_file()
+            .map(|file| file.name.split('/').next().unwrap_or_default().to_string());
+        self.rebuild_view(selected);
+    }
+
+    pub fn visible_count(&self) -> usize {
+        self.view.len()
+    }
+
+    /// Filters the listing, keeping the cursor on the selected entry if it
+    /// still matches.
+    pub fn set_filter(&mut self, filter: Option<Matcher>) {
+        self.filter = filter;
+        let selected = self.selected_file().map(|file| file.name.clone());
+        self.rebuild_view(selected);
+    }
+
+    /// Keeps a non-empty filter applied and remembers it for `search_next`.
+    pub fn confirm_filter(&mut self) {
+        match self.filter.take() {
+            Some(filter) if !filter.is_empty() => {
+                self.last_search = Some(filter.clone());
+                self.filter = Some(filter);
+            }
+            _ => self.clear_filter(),
+        }
+    }
+
+    /// Restores the full listing with the cursor kept on the selected entry.
+    pub fn clear_filter(&mut self) {
+        if let Some(filter) = self.filter.take() {
+            if !filter.is_empty() {
+                self.last_search = Some(filter);
+            }
+        }
+
+        let selected = self.selected_file().map(|file| file.name.clone());
+        self.rebuild_view(selected);
+    }
+
+    /// Jumps to the next (or previous) entry matching the last search.
+    pub fn search_next(&mut self, forward: bool) {
+        if self.filter.is_some() {
+            if forward {
+                self.on_down_pressed();
+            } else {
+                self.on_up_pressed();
+            }
+            return;
+        }
+
+        let (matcher, selected) = match (&self.last_search, self.marked_file.selected()) {
+            (Some(matcher), Some(selected)) => (matcher, selected),
+            _ => return,
+        };
+
+        let count = self.rows.len();
+        let next = (1..=count)
+            .map(|step| {
+                if forward {
+                    (selected + step) % count
+                } else {
+                    (selected + count - step % count) % count
+                }
+            })
+            .find(|&idx| {
+                self.row_file(&self.rows[idx])
+                    .is_some_and(|file| matcher.matches(&file.name).is_some())
+            });
+
+        if let Some(idx) = next {
+            self.marked_file.select(Some(idx));
+        }
+    }
+
+    pub fn toggle_hidden(&mut self) {
+        self.visibility.show_hidden = !self.visibility.show_hidden;
+        let selected = self.selected_file().map(|file| file.name.clone());
+        self.rebuild_view(selected);
+    }
+
+    pub fn toggle_ignores(&mut self) {
+        self.visibility.apply_ignores = !self.visibility.apply_ignores;
+        let selected = self.selected_file().map(|file| file.name.clone());
+        self.rebuild_view(selected);
+    }
+
+    pub fn cycle_sort_key(&mut self) {
+        let mut sort = self.files.sort_order();
+        sort.key = sort.key.next();
+        self.set_sort(sort);
+    }
+
+    pub fn toggle_sort_reverse(&mut self) {
+        let mut sort = self.files.sort_order();
+        sort.reverse = !sort.reverse;
+        self.set_sort(sort);
+    }
+
+    pub fn toggle_dirs_first(&mut self) {
+        let mut sort = self.files.sort_order();
+        sort.dirs_first = !sort.dirs_first;
+        self.set_sort(sort);
+    }
+
+    pub fn toggle_ignore_case(&mut self) {
+        let mut sort = self.files.sort_order();
+        sort.ignore_case = !sort.ignore_case;
+        self.set_sort(sort);
+    }
+
+    fn set_sort(&mut self, sort: SortOrder) {
+        let selected = self.selected_file().map(|file| file.name.clone());
+        self.files.set_sort(sort);
+        self.parent.set_sort(sort);
+        self.preview_dir.set_sort(sort);
+        self.tree.set_sort(sort);
+        self.rebuild_view(selected);
+    }
+
+    pub fn selected_file(&self) -> Option<&FileData> {
+        self.marked_file
+            .selected()
+            .and_then(|idx| self.rows.get(idx))
+            .and_then(|row| self.row_file(row))
+    }
+
+    pub fn on_right_pressed(&mut self) -> io::Result<()> {
+        let (name, raw_name, is_file, is_dir) = match self.selected_file() {
+            Some(file) => (file.name.clone(), file.raw_name().to_os_string(), file.is_file(), file.is_dir()),
+            None => return Ok(()),
+        };
+
+        if is_file {
+            open_file(self.path.join(&raw_name).as_os_str());
+        } else if is_dir && self.tree_view && !self.tree.is_expanded(&name) {
+            let sort = self.files.sort_order();
+            let dir = self.path.join(&raw_name);
+            self.tree.expand(&self.path, &raw_name, sort).map_err(|err| path_error(&dir, err))?;
+            self.rebuild_view(Some(name));
+        } else if is_dir {
+            self.change_dir(self.path.join(&raw_name))?;
+        }
+
+        Ok(())
+    }
+
+    /// Goes up a directory. In the tree view an expanded directory is
+    /// collapsed and a nested entry moves the cursor to its parent first.
+    pub fn on_left_pressed(&mut self) -> io::Result<()> {
+        let selected = self.marked_file.selected().and_then(|idx| self.rows.get(idx));
+        if let (true, Some(row)) = (self.tree_view, selected) {
+            let name = self.row_file(row).map(|file| file.name.clone());
+            match (name, row.parent.clone()) {
+                (Some(name), _) if self.tree.is_expanded(&name) => {
+                    self.tree.collapse(&name);
+                    self.rebuild_view(Some(name));
+                    return Ok(());
+                }
+                (_, Some(parent)) => {
+                    self.reselect(Some(parent));
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        match self.path.parent() {
+            Some(parent) => self.change_dir(parent.to_path_buf()),
+            None => Ok(()),
+        }
+    }
+
+    pub fn on_up_pressed(&mut self) {
+        if let Some(selected) = self.marked_file.selected() {
+            if self.rows.is_empty() {
+                return;
+            }
+            if selected > 0 {
+                self.marked_file.select(Some(selected - 1));
+            } else {
+                self.marked_file.select(Some(self.rows.len() - 1));
+            }
+        }
+    }
+
+    /// Moves the cursor `count` rows, stopping at either end.
+    pub fn move_by(&mut self, count: usize, down: bool) {
+        if let Some(selected) = self.marked_file.selected() {
+            let row = if down { selected.saturating_add(count) } else { selected.saturating_sub(count) };
+            self.select_row(row);
+        }
+    }
+
+    /// Moves the cursor half a page, like Ctrl-d and Ctrl-u in vim.
+    pub fn move_half_page(&mut self, count: usize, down: bool) {
+        self.move_by((self.page_height / 2).max(1).saturating_mul(count), down);
+    }
+
+    /// Selects `row`, or the last row if there aren't as many.
+    pub fn select_row(&mut self, row: usize) {
+        if !self.rows.is_empty() {
+            self.marked_file.select(Some(row.min(self.rows.len() - 1)));
+        }
+    }
+
+    pub fn select_last(&mut self) {
+        self.select_row(usize::MAX);
+    }
+
+    /// Selects a row on screen: `from_top` rows below the top, or the
+    /// middle one with `None`.
+    pub fn select_on_screen(&mut self, from_top: Option<usize>) {
+        let shown = self.rows.len().saturating_sub(self.scroll).min(self.page_height).max(1);
+        let row = match from_top {
+            Some(offset) => self.scroll + offset.min(shown - 1),
+            None => self.scroll + (shown - 1) / 2,
+        };
+        self.select_row(row);
+    }
+
+    /// Like `select_on_screen`, counting from the bottom.
+    pub fn select_from_bottom(&mut self, from_bottom: usize) {
+        let shown = self.rows.len().saturating_sub(self.scroll).min(self.page_height).max(1);
+        self.select_row(self.scroll + (shown - 1).saturating_sub(from_bottom));
+    }
+
+    /// Row drawn at the screen position `x`, `y`, if any.
+    pub fn row_at(&self, x: u16, y: u16) -> Option<usize> {
+        let area = self.list_area;
+        // inside the borders
+        if x <= area.x || x + 1 >= area.right() || y <= area.y || y + 1 >= area.bottom() {
+            return None;
+        }
+
+        let row = self.scroll + (y - area.y - 1) as usize;
+        (row < self.rows.len()).then_some(row)
+    }
+
+    /// Records where the file list is about to be drawn and scrolls the
+    /// way `List` will, so the rows on screen are known before drawing.
+    pub fn set_list_area(&mut self, area: Rect) {
+        let height = area.height.saturating_sub(2) as usize;
+        self.list_area = area;
+        self.page_height = height;
+        let selected = match self.marked_file.selected() {
+            Some(selected) if height > 0 && !self.rows.is_empty() => selected,
+            _ => {
+                self.scroll = 0;
+                return;
+            }
+        };
+
+        self.scroll = self.scroll.min(self.rows.len() - 1);
+        if selected >= self.scroll + height {
+            self.scroll = selected + 1 - height;
+        } else if selected < self.scroll {
+            self.scroll = selected;
+        }
+    }
+
+    pub fn on_down_pressed(&mut self) {
+        if let Some(selected) = self.marked_file.selected() {
+            if self.rows.is_empty() {
+                return;
+            }
+            if selected >= self.rows.len() - 1 {
+                self.marked_file.select(Some(0));
+            } else {
+                self.marked_file.select(Some(selected + 1));
+            }
+        }
+    }
+
+    /// Opens `dir` with the cursor on `name`.
+    pub fn goto(&mut self, dir: PathBuf, name: Option<String>) -> io::Result<()> {
+        self.change_dir(dir)?;
+        self.reselect(name);
+
+        Ok(())
+    }
+
+    /// Returns to the directory visited before the current one.
+    pub fn go_back(&mut self) -> io::Result<()> {
+        let dir = match self.back.pop() {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+
+        let current = self.path.clone();
+        self.open(dir)?;
+        self.forward.push(current);
+
+        Ok(())
+    }
+
+    /// Undoes a `go_back`.
+    pub fn go_forward(&mut self) -> io::Result<()> {
+        let dir = match self.forward.pop() {
+            Some(dir) => dir,
+            None => return Ok(()),
+        };
+
+        let current = self.path.clone();
+        self.open(dir)?;
+        self.back.push(current);
+
+        Ok(())
+    }
+
+    /// Opens `dir`, recording the current directory in the history.
+    fn change_dir(&mut self, dir: PathBuf) -> io::Result<()> {
+        if dir == self.path {
+            return self.open(dir);
+        }
+
+        let current = self.path.clone();
+        self.open(dir)?;
+        self.back.push(current);
+        self.forward.clear();
+
+        Ok(())
+    }
+
+    /// Opens `dir`, staying in the current directory if it can't be read.
+    /// The cursor goes to the directory we came from when going up, or
+    /// else to the entry selected when `dir` was last left.
+    fn open(&mut self, dir: PathBuf) -> io::Result<()> {
+        // the range is made of rows of the directory being left
+        self.commit_visual();
+        self.prune_marked();
+        if let Some(file) = self.selected_file() {
+            self.selections.insert(self.path.clone(), file.name.clone());
+        }
+
+        let previous = std::mem::replace(&mut self.path, dir);
+        if let Err(err) = self.open_dir() {
+            let err = path_error(&self.path, err);
+            self.path = previous;
+            return Err(err);
+        }
+
+        let selected = match previous.file_name() {
+            Some(name) if previous.parent() == Some(self.path.as_path()) => Some(display_name(name)),
+            _ => self.selections.get(&self.path).cloned(),
+        };
+        self.reselect(selected);
+
+        Ok(())
+    }
+
+    /// Makes this tab's directory the working directory, which commands
+    /// like `:n` and `:cd` are relative to. Only the active tab may.
+    pub fn enter(&self) -> io::Result<()> {
+        env::set_current_dir(&self.path)
+    }
+
+    fn open_dir(&mut self) -> io::Result<()> {
+        self.files.load(&self.path)?;
+        self.visibility.set_dir(&self.path);
+        self.filter = None;
+        self.tree.clear();
+        self.marked_file.select(None);
+        self.scroll = 0;
+        self.rebuild_view(None);
+        self.load_parent();
+
+        Ok(())
+    }
+
+    /// Applies the active filter to the listing and moves the cursor to
+    /// `selected` if it is still visible.
+    fn rebuild_view(&mut self, selected: Option<String>) {
+        let (view, hidden_count) = filter_files(
+            self.files.files(),
+            self.files.path(),
+            &self.visibility,
+            self.filter.as_ref(),
+        );
+        self.view = view;
+        self.hidden_count = hidden_count;
+
+        let top: Vec<_> = self.view.iter().filter_map(|entry| self.files.get(entry.idx)).collect();
+        let rows = self.tree.flatten(&top, |file| self.visibility.is_hidden(self.files.path(), file));
+        self.rows = rows;
+        self.reselect(selected);
+        if self.visual_start.is_some_and(|start| start >= self.rows.len()) {
+            self.visual_start = self.rows.len().checked_sub(1);
+        }
+    }
+
+    fn reset_selection(&mut self) {
+        if self.rows.is_empty() {
+            self.marked_file.select(None);
+        } else {
+            self.marked_file.select(Some(0));
+        }
+    }
+
+    /// Keeps the cursor on the same entry after the view changed,
+    /// falling back to the nearest valid index if it is gone.
+    pub fn reselect(&mut self, name: Option<String>) {
+        let position = name.and_then(|name| {
+            self.rows.iter().position(|row| {
+                self.row_file(row).is_some_and(|file| file.name == name)
+            })
+        });
+
+        match position {
+            Some(idx) => self.marked_file.select(Some(idx)),
+            None => self.clamp_selection(),
+        }
+    }
+
+    fn clamp_selection(&mut self) {
+        match self.marked_file.selected() {
+            Some(_) if self.rows.is_empty() => self.marked_file.select(None),
+            Some(idx) if idx >= self.rows.len() => {
+                self.marked_file.select(Some(self.rows.len() - 1))
+            }
+            None => self.reset_selection(),
+            _ => {}
+        }
+    }
+}
+
+fn open_file(file_name: &OsStr) {
+    Command::new("xdg-open")
+        .arg(file_name)
+        .stderr(Stdio::null())
+        .spawn().ok();
+}
\ No newline at end of file
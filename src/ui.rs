@@ -0,0 +1,755 @@
+use std::ops::Range;
+use std::path::Path;
+
+use tui::backend::Backend;
+use tui::layout::{
+    Constraint,
+    Direction,
+    Layout,
+    Rect
+};
+use tui::style::{
+    Color,
+    Modifier,
+    Style
+};
+use tui::text::{
+    Span,
+    Spans
+};
+use tui::widgets::{
+    Block,
+    BorderType,
+    Borders,
+    Clear,
+    List,
+    ListItem,
+    ListState,
+    Paragraph,
+    Tabs
+};
+use tui::Frame;
+
+use humansize::{format_size, DECIMAL};
+
+use rufile::bookmarks::Bookmarks;
+use rufile::command_input::input::{CommandHandler, InputMode};
+use rufile::command_input::jobs::{JobQueue, JobStatus, Progress};
+use rufile::entry::file_data::{display_name, escape_name, FileData};
+use rufile::entry::tree::TreeRow;
+
+use crate::app::{App, PaneLayout};
+use crate::results::{FindView, GrepView, ResultKind, Results};
+use crate::tab::Tab;
+
+pub fn draw<B: Backend>(f: &mut Frame<B>, app: &mut App) {
+    // the tab bar only takes up space once there is more than one tab
+    let tab_bar_height = if app.tabs.len() > 1 { 1 } else { 0 };
+    let chunks = Layout::default()
+        .horizontal_margin(1)
+        .direction(Direction::Vertical)
+        .constraints([
+                Constraint::Length(tab_bar_height),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ].as_ref()
+        )
+        .split(f.size());
+
+    if tab_bar_height > 0 {
+        f.render_widget(render_tab_bar(app), chunks[0]);
+    }
+
+    match app.layout {
+        PaneLayout::TwoPane => draw_two_pane(f, app, chunks[1]),
+        PaneLayout::Miller => draw_miller(f, app, chunks[1]),
+        PaneLayout::Dual => draw_dual(f, app, chunks[1]),
+    }
+
+    if let Some(list) = &mut app.bookmark_list {
+        let area = centered_rect(60, 50, chunks[1]);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(render_bookmarks(&app.bookmarks), area, list);
+    }
+
+    if let Some(list) = &mut app.job_list {
+        let area = centered_rect(80, 50, chunks[1]);
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(render_jobs(&app.jobs), area, list);
+    }
+
+    // the running job takes the right end of the status line
+    let mut status_chunk = chunks[2];
+    if let Some(summary) = job_summary(&app.jobs) {
+        let width = (summary.chars().count() as u16).min(status_chunk.width / 2);
+        status_chunk.width -= width;
+        let summary_chunk = Rect {
+            x: status_chunk.x + status_chunk.width,
+            width,
+            ..status_chunk
+        };
+        f.render_widget(Paragraph::new(Span::styled(summary, Style::default().fg(Color::Cyan))), summary_chunk);
+    }
+
+    let prompt = app.prompt();
+    let prompt_width = prompt.chars().count() as u16;
+    let input_chunk = render_input_field(prompt, &app.command);
+    f.render_widget(input_chunk, status_chunk);
+
+    if matches!(app.command.input_mode,
+            InputMode::Editing | InputMode::Search | InputMode::Find) {
+        f.set_cursor(
+            chunks[2].x + prompt_width + app.command.input.chars().count() as u16,
+            chunks[2].y,
+        )
+    }
+}
+
+/// A `percent_x` by `percent_y` rectangle in the middle of `area`.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = area.height * percent_y / 100;
+
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+/// Lists the jobs with their progress, or how they ended.
+fn render_jobs<'a>(jobs: &JobQueue) -> List<'a> {
+    let items: Vec<_> = jobs
+        .entries()
+        .iter()
+        .map(|entry| {
+            let (status, color) = match &entry.status {
+                JobStatus::Running if entry.control.is_paused() => (String::from("paused"), Color::Yellow),
+                JobStatus::Running => (progress_text(&entry.progress), Color::Cyan),
+                JobStatus::Queued => (String::from("queued"), Color::Gray),
+                JobStatus::Done => (String::from("done"), Color::Green),
+                JobStatus::Cancelled => (String::from("cancelled"), Color::DarkGray),
+                JobStatus::Failed(message) => (format!("failed: {}", message), Color::Red),
+            };
+            let mut lines = vec![Spans::from(Span::raw(entry.job.to_string()))];
+            if entry.status == JobStatus::Running {
+                lines.push(Spans::from(Span::styled(
+                    format!("  {} {}", progress_bar(&entry.progress, 20), status),
+                    Style::default().fg(color))));
+            } else {
+                lines.push(Spans::from(Span::styled(format!("  {}", status), Style::default().fg(color))));
+            }
+            ListItem::new(lines)
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(" Jobs (p pause, x cancel, c clear) ")
+                .border_style(Style::default().fg(Color::Cyan))
+                .border_type(BorderType::Thick),
+        )
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .highlight_symbol(">")
+}
+
+/// The running job and how many are waiting, for the status line.
+fn job_summary(jobs: &JobQueue) -> Option<String> {
+    let entry = jobs.entries().iter().find(|entry| entry.status == JobStatus::Running)?;
+    let queued = jobs.entries().iter().filter(|entry| entry.status == JobStatus::Queued).count();
+
+    let mut summary = format!("{} {:.0}%", entry.job, entry.progress.ratio() * 100.0);
+    if entry.control.is_paused() {
+        summary.push_str(" paused");
+    }
+    if queued > 0 {
+        summary.push_str(&format!(" (+{} queued)", queued));
+    }
+
+    Some(summary)
+}
+
+/// E.g. `[#####-----]`, `width` characters between the brackets.
+fn progress_bar(progress: &Progress, width: usize) -> String {
+    let filled = (progress.ratio() * width as f64) as usize;
+    format!("[{}{}]", "#".repeat(filled), "-".repeat(width - filled))
+}
+
+/// Percentage, time left, bytes and files of a running job.
+fn progress_text(progress: &Progress) -> String {
+    let mut text = format!("{:>3.0}%", progress.ratio() * 100.0);
+    if let Some(eta) = progress.eta() {
+        let secs = eta.as_secs();
+        text.push_str(&format!("  ETA {}:{:02}", secs / 60, secs % 60));
+    }
+    if progress.bytes_total > 0 {
+        text.push_str(&format!("  {}/{}",
+            format_size(progress.bytes_done, DECIMAL),
+            format_size(progress.bytes_total, DECIMAL)));
+    }
+    text.push_str(&format!("  {}/{} files", progress.files_done, progress.files_total));
+
+    text
+}
+
+/// Lists the bookmarks, flagging those whose directory is gone.
+fn render_bookmarks<'a>(bookmarks: &Bookmarks) -> List<'a> {
+    let name_width = bookmarks.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0);
+
+    let items: Vec<_> = bookmarks
+        .iter()
+        .map(|(name, path)| {
+            let mut spans = vec![
+                Span::styled(format!("{:<width$}  ", name, width = name_width),
+                    Style::default().fg(Color::Yellow)),
+                Span::raw(path.to_string_lossy().into_owned()),
+            ];
+            if !path.is_dir() {
+                spans.push(Span::styled(" (missing)", Style::default().fg(Color::Red)));
+            }
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(" Bookmarks ")
+                .border_style(Style::default().fg(Color::Cyan))
+                .border_type(BorderType::Thick),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(Color::Cyan)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">")
+}
+
+fn render_tab_bar<'a>(app: &App) -> Tabs<'a> {
+    let titles: Vec<_> = app.tabs
+        .iter()
+        .enumerate()
+        .map(|(idx, tab)| Spans::from(format!("{}:{}", idx + 1, tab.title())))
+        .collect();
+
+    Tabs::new(titles)
+        .select(app.active)
+        .style(Style::default().fg(Color::Gray))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+}
+
+fn draw_two_pane<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+            ].as_ref()
+        )
+        .split(area);
+
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Length(12),
+                Constraint::Length(6),
+                Constraint::Length(2)
+            ].as_ref()
+        )
+        .split(main_chunks[1]);
+
+    let (list, mut paragraphs) = render_files(app.tab(), &app.results, app.preview_scroll);
+
+    app.preview_area = right[0];
+    draw_current(f, app, list, main_chunks[0]);
+    f.render_widget(paragraphs.remove(1), right[1]);
+    f.render_widget(paragraphs.remove(0), right[0]);
+}
+
+fn draw_miller<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(20),
+                Constraint::Percentage(40),
+                Constraint::Percentage(40),
+            ].as_ref()
+        )
+        .split(area);
+
+    let right = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [
+                Constraint::Min(3),
+                Constraint::Length(6),
+            ].as_ref()
+        )
+        .split(main_chunks[2]);
+
+    let tab = app.tab();
+    let (entries, position) = tab.parent_entries();
+    let title = display_name(tab.parent.path().as_os_str());
+    let mut parent_state = ListState::default();
+    parent_state.select(position);
+    f.render_stateful_widget(render_dir_column(title, &entries), main_chunks[0], &mut parent_state);
+
+    let (list, mut paragraphs) = render_files(tab, &app.results, app.preview_scroll);
+    app.preview_area = right[0];
+    draw_current(f, app, list, main_chunks[1]);
+
+    let tab = app.tab();
+    match (&app.results, tab.preview_entries()) {
+        (None, Some(entries)) => {
+            let title = display_name(tab.preview_dir.path().as_os_str());
+            f.render_widget(render_dir_column(title, &entries), right[0]);
+        }
+        _ => f.render_widget(paragraphs.remove(0), right[0]),
+    }
+    f.render_widget(paragraphs.pop().unwrap(), right[1]);
+}
+
+fn draw_dual<B: Backend>(f: &mut Frame<B>, app: &mut App, area: Rect) {
+    app.preview_area = Rect::default();
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [
+                Constraint::Percentage(50),
+                Constraint::Percentage(50),
+            ].as_ref()
+        )
+        .split(area);
+
+    // the active pane keeps its side when switching with Tab
+    let (active_area, other_area) = if app.active < app.other {
+        (main_chunks[0], main_chunks[1])
+    } else {
+        (main_chunks[1], main_chunks[0])
+    };
+
+    let list = render_file_list(app.tab(), true);
+    draw_current(f, app, list, active_area);
+
+    let other = &mut app.tabs[app.other];
+    let list = render_file_list(other, false);
+    other.set_list_area(other_area);
+    f.render_stateful_widget(list, other_area, &mut other.marked_file);
+}
+
+/// Renders the current listing, or the search results in its place.
+fn draw_current<B: Backend>(f: &mut Frame<B>, app: &mut App, list: List, area: Rect) {
+    match &mut app.results {
+        Some(results) => {
+            let list = render_results(results);
+            f.render_stateful_widget(list, area, &mut results.selected);
+        }
+        None => {
+            let tab = &mut app.tabs[app.active];
+            tab.set_list_area(area);
+            f.render_stateful_widget(list, area, &mut tab.marked_file);
+        }
+    }
+}
+
+/// A plain listing of a directory next to the current one.
+fn render_dir_column<'a>(title: String, files: &[&FileData]) -> List<'a> {
+    let items: Vec<_> = files
+        .iter()
+        .map(|file| {
+            let file_color = file_style(file);
+            let mut spans = name_spans(file, &[], file_color);
+            if file.is_dir() {
+                spans.push(Span::styled("/", file_color));
+            }
+            spans.extend(link_target(file));
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(format!(" {} ", title))
+                .border_style(Style::default().fg(Color::DarkGray))
+                .border_type(BorderType::Thick),
+        )
+        .highlight_style(
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )
+}
+
+fn render_files<'a>(
+    tab: &Tab,
+    results: &Option<Results>,
+    preview_scroll: u16,
+) -> (List<'a>, Vec<Paragraph<'a>>) {
+    let file_list_view = render_file_list(tab, true);
+
+    let selected_file = tab.selected_file();
+
+    let preview = match results {
+        Some(results) => render_results_preview(results),
+        None => render_preview(selected_file, preview_scroll),
+    };
+
+    let paragraphs = vec![
+        preview,
+        render_info(selected_file),
+    ];
+
+    (file_list_view, paragraphs)
+}
+
+fn render_file_list<'a>(tab: &Tab, active: bool) -> List<'a> {
+    let mut title = format!(" {} [{}] ", display_name(tab.path.as_os_str()), tab.files.sort_order());
+    if tab.hidden_count > 0 {
+        title.push_str(&format!("({} hidden) ", tab.hidden_count));
+    }
+    if let Some(filter) = &tab.filter {
+        title.push_str(&format!("/{} ({}, {}/{}) ",
+            filter.pattern(),
+            filter.kind(),
+            tab.visible_count(),
+            tab.files.len()));
+    }
+    if !tab.marked.is_empty() {
+        title.push_str(&format!("({} selected) ", tab.marked.len()));
+    }
+    
+    let files_block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::White))
+        .title(title)
+        .border_style(Style::default().fg(if active { Color::Yellow } else { Color::DarkGray }))
+        .border_type(BorderType::Thick);
+
+    // selected entries get a `+`, but only while there are any
+    let show_marks = !tab.marked.is_empty() || tab.is_visual();
+    let items: Vec<_> = tab.visible_files()
+        .enumerate()
+        .map(|(idx, (row, file, positions))| {
+            let file_color = file_style(file);
+
+            let mut spans = vec![];
+            if show_marks {
+                spans.push(if tab.is_marked(idx) {
+                    Span::styled("+", Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD))
+                } else {
+                    Span::raw(" ")
+                });
+            }
+            if row.depth > 0 {
+                spans.push(Span::styled(tree_guides(row), Style::default().fg(Color::DarkGray)));
+            }
+            spans.extend(name_spans(file, positions, file_color));
+            if file.is_dir() {
+                spans.push(Span::styled("/", file_color));
+            }
+            spans.extend(link_target(file));
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+
+    let highlight_style = if active {
+        Style::default()
+            .bg(Color::Yellow)
+            .fg(Color::Black)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().add_modifier(Modifier::REVERSED)
+    };
+
+    let file_view = List::new(items)
+        .block(files_block)
+        .highlight_style(highlight_style)
+        .highlight_symbol(">");
+
+    file_view
+}
+
+/// Directories are blue, symlinks cyan and broken symlinks red.
+fn file_style(file: &FileData) -> Style {
+    if file.is_broken_link() {
+        Style::default().fg(Color::Red).add_modifier(Modifier::CROSSED_OUT)
+    } else if file.is_link() {
+        Style::default().fg(Color::Cyan)
+    } else if file.is_dir() {
+        Style::default().fg(Color::Blue)
+    } else {
+        Style::default()
+    }
+}
+
+/// The ` -> target` suffix of a symlink.
+fn link_target<'a>(file: &FileData) -> Option<Span<'a>> {
+    file.link_target().map(|target| {
+        Span::styled(format!(" -> {}", target.display()), Style::default().fg(Color::DarkGray))
+    })
+}
+
+/// Indentation with guide lines for an entry of an expanded directory.
+fn tree_guides(row: &TreeRow) -> String {
+    let mut guides: String = row.guides
+        .iter()
+        .skip(1)
+        .map(|&more| if more { "│ " } else { "  " })
+        .collect();
+    guides.push_str(if row.last { "└ " } else { "├ " });
+
+    guides
+}
+
+fn render_results<'a>(results: &Results) -> List<'a> {
+    let status = if results.is_done() { "done" } else { "searching..." };
+    let (title, items) = match &results.kind {
+        ResultKind::Find(find) => (
+            format!(" find: {} ({}) {} results, {} ",
+                find.pattern, find.kind, find.results.len(), status),
+            render_find_items(find),
+        ),
+        ResultKind::Grep(grep) => (
+            format!(" grep: {} (hidden {}) {} hits, {} ",
+                grep.regex, if grep.show_hidden { "on" } else { "off" }, grep.hits.len(), status),
+            render_grep_items(grep),
+        ),
+    };
+
+    let results_block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default().fg(Color::White))
+        .title(title)
+        .border_style(Style::default().fg(Color::Magenta))
+        .border_type(BorderType::Thick);
+
+    List::new(items)
+        .block(results_block)
+        .highlight_style(
+            Style::default()
+                .bg(Color::Magenta)
+                .fg(Color::Black)
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol(">")
+}
+
+fn render_find_items<'a>(find: &FindView) -> Vec<ListItem<'a>> {
+    find.results
+        .iter()
+        .map(|result| {
+            let mut file_color = Style::default();
+            if result.is_dir {
+                file_color = file_color.fg(Color::Blue);
+            }
+
+            let path = result.path.to_string_lossy();
+            // results are highlighted in the file name, after "dir/"
+            let name_start = path.chars().count()
+                - result.path.file_name().map_or(0, |name| name.to_string_lossy().chars().count());
+            let positions: Vec<usize> = result.positions
+                .iter()
+                .map(|pos| pos + name_start)
+                .collect();
+
+            let mut spans = highlight_matches(&path, &positions, file_color);
+            if result.is_dir {
+                spans.push(Span::styled("/", file_color));
+            }
+            ListItem::new(Spans::from(spans))
+        })
+        .collect()
+}
+
+fn render_grep_items<'a>(grep: &GrepView) -> Vec<ListItem<'a>> {
+    grep.hits
+        .iter()
+        .map(|hit| {
+            let location = format!("{}:{}: ", hit.path.to_string_lossy(), hit.line);
+            let snippet = hit.text.trim_start();
+            let trimmed = hit.text.len() - snippet.len();
+            let ranges: Vec<_> = hit.matches
+                .iter()
+                .map(|range| range.start.saturating_sub(trimmed)..range.end.saturating_sub(trimmed))
+                .collect();
+
+            let mut spans = vec![Span::styled(location, Style::default().fg(Color::Cyan))];
+            spans.extend(highlight_matches(snippet, &char_positions(snippet, &ranges), Style::default()));
+            ListItem::new(Spans::from(spans))
+        })
+        .collect()
+}
+
+/// Shows the lines around the selected grep hit.
+fn render_results_preview<'a>(results: &Results) -> Paragraph<'a> {
+    let mut lines: Vec<Spans> = vec![];
+    let mut title = String::from(" Preview ");
+
+    if let (Some(hit), ResultKind::Grep(grep)) = (results.selected_hit(), &results.kind) {
+        title = format!(" {}:{} ", hit.path.to_string_lossy(), hit.line);
+
+        if let Some(context) = results.context() {
+            for (number, text) in context.iter().cloned() {
+                let ranges: Vec<_> = grep.regex.find_iter(&text).map(|m| m.range()).collect();
+                let mut number_style = Style::default().fg(Color::DarkGray);
+                if number == hit.line {
+                    number_style = number_style.fg(Color::Yellow);
+                }
+
+                let mut spans = vec![Span::styled(format!("{:>4} ", number), number_style)];
+                spans.extend(highlight_matches(&text, &char_positions(&text, &ranges), Style::default()));
+                lines.push(Spans::from(spans));
+            }
+        }
+    }
+
+    Paragraph::new(lines)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::LightBlue))
+                .title(title)
+                .border_type(BorderType::Thick),
+        )
+}
+
+/// Converts byte ranges within `text` to the char positions they cover.
+fn char_positions(text: &str, ranges: &[Range<usize>]) -> Vec<usize> {
+    text.char_indices()
+        .enumerate()
+        .filter(|(_, (byte, _))| ranges.iter().any(|range| range.contains(byte)))
+        .map(|(idx, _)| idx)
+        .collect()
+}
+
+/// Splits `name` into spans, emphasizing the characters at `positions`.
+fn highlight_matches<'a>(name: &str, positions: &[usize], style: Style) -> Vec<Span<'a>> {
+    let matched_style = style.fg(Color::LightRed).add_modifier(Modifier::UNDERLINED);
+
+    style_chars(name, |idx| if positions.contains(&idx) { matched_style } else { style })
+}
+
+/// The name of a listed file with filter matches and the escapes of bytes
+/// that aren't valid UTF-8 highlighted.
+fn name_spans<'a>(file: &FileData, positions: &[usize], style: Style) -> Vec<Span<'a>> {
+    // nested entries are named by their path below the listed directory
+    let raw_name = Path::new(file.raw_name()).file_name().unwrap_or(file.raw_name());
+    let (name, escaped) = escape_name(raw_name);
+    let matched_style = style.fg(Color::LightRed).add_modifier(Modifier::UNDERLINED);
+    let escaped_style = style.fg(Color::Magenta).add_modifier(Modifier::REVERSED);
+
+    style_chars(&name, |idx| {
+        if escaped.contains(&idx) {
+            escaped_style
+        } else if positions.contains(&idx) {
+            matched_style
+        } else {
+            style
+        }
+    })
+}
+
+/// Splits `text` into spans of chars that get the same style.
+fn style_chars<'a>(text: &str, style_of: impl Fn(usize) -> Style) -> Vec<Span<'a>> {
+    let mut spans = vec![];
+    let mut run = String::new();
+    let mut run_style = Style::default();
+
+    for (idx, c) in text.chars().enumerate() {
+        let style = style_of(idx);
+        if style != run_style && !run.is_empty() {
+            spans.push(Span::styled(std::mem::take(&mut run), run_style));
+        }
+        run_style = style;
+        run.push(c);
+    }
+
+    if !run.is_empty() {
+        spans.push(Span::styled(run, run_style));
+    }
+
+    spans
+}
+
+/// Shows the head of the selected file, scrolled down `scroll` lines.
+fn render_preview<'a>(selected_file: Option<&FileData>, scroll: u16) -> Paragraph<'a> {
+    let mut preview = String::from("");
+    if let Some(file) = selected_file {
+        if let Ok(text) = file.preview_lines(scroll as usize + 100) {
+            preview = text;
+        }
+    }
+
+    Paragraph::new(preview)
+        .scroll((scroll, 0))
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::LightBlue))
+                .title(" Preview ")
+                .border_type(BorderType::Thick),
+        )
+}
+
+fn render_info<'a>(selected_file: Option<&FileData>) -> Paragraph<'a> {
+    let mut info = String::from("");
+    if let Some(file) = selected_file {
+        info = file.info();
+    }
+
+    Paragraph::new(info)
+        .style(Style::default().fg(Color::White))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Green))
+                .title(" Info ")
+                .border_type(BorderType::Thick),
+        )
+}
+
+fn render_input_field<'a>(prompt: String, command: &'a CommandHandler) -> Paragraph<'a> {
+    let text = match command.input_mode {
+        InputMode::Error => {
+            let message = command.error.as_deref().unwrap_or("Invalid command");
+            Spans::from(vec![Span::styled(message, 
+                        Style::default()
+                        .fg(Color::Red)
+                        .add_modifier(Modifier::REVERSED))
+            ])
+        },
+        _ => Spans::from(vec![
+            Span::styled(prompt, Style::default().fg(Color::Gray)),
+            Span::raw(command.input.as_str()),
+        ])
+    };
+
+    Paragraph::new(text)
+        .style(match command.input_mode {
+            InputMode::Error => Style::default(),
+            _ => Style::default(),
+        })
+        .block(Block::default())
+
+}
\ No newline at end of file
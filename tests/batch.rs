@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+use std::fs;
+
+use rufile::batch::{read_lines, Batch, Exec};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_batch_run() {
+    let tmp = TempDir::new("batch");
+    let dir = tmp.display();
+
+    let mut batch = Batch::new(false);
+    assert_eq!(Ok(false), batch.run("  "));
+    assert_eq!(Ok(false), batch.run("# comment"));
+    assert_eq!(Ok(true), batch.run(&format!(":n d {}/build", dir)));
+    assert_eq!(Ok(true), batch.run(&format!(":n f {}/a", dir)));
+    assert_eq!(Ok(true), batch.run(&format!(":r {}/a {}/build/b", dir, dir)));
+    assert!(PathBuf::from(format!("{}/build/b", dir)).is_file());
+
+    assert_eq!(Err("missing file name".to_string()), batch.run(":d"));
+    assert_eq!(Err("invalid command".to_string()), batch.run(":x"));
+    for malformed in ["é", ":é", "é x", ":", "::", "d"] {
+        assert_eq!(Err("invalid command".to_string()), batch.run(malformed));
+    }
+    assert_eq!(Err("not available in batch mode".to_string()), batch.run(":z src"));
+    assert!(batch.run(&format!(":r {}/missing {}/x", dir, dir)).is_err());
+
+    assert_eq!(Ok(true), batch.run(&format!(":d {}/build", dir)));
+    assert!(!PathBuf::from(format!("{}/build", dir)).exists());
+
+    let mut read_only = Batch::new(true);
+    assert_eq!(Err("read-only mode".to_string()), read_only.run(&format!(":n f {}/c", dir)));
+}
+
+#[test]
+fn test_read_lines() {
+    let dir = TempDir::new("batch_script");
+    let script = dir.join("script.rf");
+    fs::write(&script, ":n d a\n\n:n f b\n").unwrap();
+
+    let lines = read_lines(&[
+        Exec::Command(":n d x".to_string()),
+        Exec::Script(script.clone()),
+    ]).unwrap();
+    let location = |line| format!("{}:{}", script.display(), line);
+    assert_eq!(vec![
+        ("-c".to_string(), ":n d x".to_string()),
+        (location(1), ":n d a".to_string()),
+        (location(2), "".to_string()),
+        (location(3), ":n f b".to_string()),
+    ], lines);
+
+    fs::remove_file(&script).unwrap();
+    assert!(read_lines(&[Exec::Script(script)]).is_err());
+}
\ No newline at end of file
@@ -0,0 +1,30 @@
+use std::path::Path;
+use std::fs;
+
+use rufile::bookmarks::Bookmarks;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_bookmarks_persist() {
+    let dir = TempDir::new("bookmarks");
+    let file = dir.join("data").join("bookmarks.toml");
+
+    let mut bookmarks = Bookmarks::load(&file).unwrap();
+    assert!(bookmarks.is_empty());
+
+    bookmarks.set("work", &dir).unwrap();
+    bookmarks.set("a", Path::new("/gone")).unwrap();
+    bookmarks.set("tmp", Path::new("/tmp")).unwrap();
+    bookmarks.remove("tmp").unwrap();
+
+    let bookmarks = Bookmarks::load(&file).unwrap();
+    let names: Vec<_> = bookmarks.iter().map(|(name, _)| name).collect();
+    assert_eq!(vec!["a", "work"], names);
+    assert_eq!(Some(dir.as_path()), bookmarks.get("work"));
+    assert_eq!(Some(Path::new("/gone")), bookmarks.get("a"));
+
+    fs::write(&file, "bookmarks = 1").unwrap();
+    assert!(Bookmarks::load(&file).is_err());
+}
\ No newline at end of file
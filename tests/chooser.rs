@@ -0,0 +1,37 @@
+use std::ffi::OsStr;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::fs;
+
+use rufile::chooser::{ChooseMode, Chooser};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_chooser_output() {
+    let mut chooser = Chooser {
+        mode: ChooseMode::Files,
+        output: None,
+        null: false,
+    };
+    let raw = Path::new(OsStr::from_bytes(b"/tmp/caf\xe9"));
+    let paths = [Path::new("/tmp/a b"), raw];
+
+    assert_eq!(b"/tmp/a b\n/tmp/caf\xe9\n".to_vec(), chooser.format(&paths));
+    chooser.null = true;
+    assert_eq!(b"/tmp/a b\0/tmp/caf\xe9\0".to_vec(), chooser.format(&paths));
+    assert!(chooser.format(&[]).is_empty());
+
+    let dir = TempDir::new("chooser");
+    let file = dir.join("chosen");
+    chooser.output = Some(file.clone());
+    let mut stdout = vec![];
+    chooser.write(&paths, &mut stdout).unwrap();
+    assert_eq!(chooser.format(&paths), fs::read(&file).unwrap());
+    assert!(stdout.is_empty());
+
+    chooser.output = None;
+    chooser.write(&paths, &mut stdout).unwrap();
+    assert_eq!(chooser.format(&paths), stdout);
+}
\ No newline at end of file
@@ -0,0 +1,74 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+use rufile::args::{Args, ArgsError};
+use rufile::batch::Exec;
+use rufile::chooser::{ChooseMode, Chooser};
+
+fn parse(args: &[&str]) -> Result<Args, ArgsError> {
+    Args::parse(args.iter().map(OsString::from))
+}
+
+#[test]
+fn test_parse_args() {
+    assert_eq!(Args::default(), parse(&[]).unwrap());
+
+    let args = parse(&["-H", "--read-only", "--config", "my.toml", "src"]).unwrap();
+    assert_eq!(Some(PathBuf::from("src")), args.path);
+    assert_eq!(Some(PathBuf::from("my.toml")), args.config);
+    assert!(args.show_hidden);
+    assert!(args.read_only);
+    assert!(!args.help);
+
+    let args = parse(&["--config=my.toml", "-r", "-V", "--help"]).unwrap();
+    assert_eq!(Some(PathBuf::from("my.toml")), args.config);
+    assert!(args.read_only && args.version && args.help);
+
+    let args = parse(&["--", "-H"]).unwrap();
+    assert_eq!(Some(PathBuf::from("-H")), args.path);
+    assert!(!args.show_hidden);
+}
+
+#[test]
+fn test_parse_args_errors() {
+    assert_eq!(Err(ArgsError::UnknownOption("-x".to_string())), parse(&["-x"]));
+    assert_eq!(Err(ArgsError::UnknownOption("--hidden=yes".to_string())), parse(&["--hidden=yes"]));
+    assert_eq!(Err(ArgsError::MissingValue("--config".to_string())), parse(&["--config"]));
+    assert_eq!(Err(ArgsError::UnexpectedArgument(OsString::from("b"))), parse(&["a", "b"]));
+    assert_eq!(Err(ArgsError::Conflict("--choose-dir".to_string(), "--choose-files".to_string())),
+        parse(&["--choose-dir", "--choose-files"]));
+}
+
+#[test]
+fn test_parse_exec_args() {
+    let args = parse(&["-c", ":n d build", "--exec=build.rf", "--command", ":p", "out"]).unwrap();
+    assert_eq!(vec![
+        Exec::Command(":n d build".to_string()),
+        Exec::Script(PathBuf::from("build.rf")),
+        Exec::Command(":p".to_string()),
+    ], args.exec);
+    assert_eq!(Some(PathBuf::from("out")), args.path);
+
+    assert_eq!(Err(ArgsError::MissingValue("-c".to_string())), parse(&["-c"]));
+}
+
+#[test]
+fn test_parse_chooser_args() {
+    assert_eq!(Some(Chooser {
+        mode: ChooseMode::Files,
+        output: None,
+        null: false,
+    }), parse(&["--choose-files", "src"]).unwrap().choose);
+
+    assert_eq!(Some(Chooser {
+        mode: ChooseMode::Files,
+        output: Some(PathBuf::from("/tmp/chosen")),
+        null: true,
+    }), parse(&["-0", "--choose-files=/tmp/chosen"]).unwrap().choose);
+
+    assert_eq!(Some(Chooser {
+        mode: ChooseMode::Dir,
+        output: None,
+        null: false,
+    }), parse(&["--choose-dir=-"]).unwrap().choose);
+}
\ No newline at end of file
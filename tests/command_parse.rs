@@ -1,6 +1,12 @@
-use std::{path::Path};
+use std::ffi::OsStr;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::fs;
 
-use rufile::command_input::input::{CommandHandler, InputMode};
+use rufile::command_input::input::{AppCommand, CommandHandler, InputMode};
+
+mod common;
+use common::TempDir;
 
 #[test]
 fn test_copy_command() {
@@ -14,12 +20,12 @@ fn test_copy_command() {
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":c c".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":cc c c".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 }
@@ -37,12 +43,12 @@ fn test_cut_command() {
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":m m".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":mm m m".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 }
@@ -59,12 +65,12 @@ fn test_delete_command() {
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":d d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":d d d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 }
@@ -87,27 +93,27 @@ fn test_edit_command() {
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":e d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":e d d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":e 10".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":e 1000".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":e 1a0".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 }
@@ -124,7 +130,7 @@ fn test_rename_command() {
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":r d d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 }
@@ -141,27 +147,27 @@ fn test_create_command() {
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":n".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":n s".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":n s file".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":n d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
 
     command.input = ":n f".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 }
@@ -178,37 +184,152 @@ fn test_other_command() {
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
     
     command.input = ":1".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":h".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ": h d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":cp d".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = ":cc".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
 
     assert_eq!(InputMode::Error, command.input_mode);
 
     command.input = "c".to_string();
-    command.exec(Some(&file_name));
+    command.exec(Some(file_name.as_ref()));
+
+    assert_eq!(InputMode::Error, command.input_mode);
+}
+
+#[test]
+fn test_grep_command() {
+    let mut command = CommandHandler::default();
 
+    command.input = ":g".to_string();
+    assert_eq!(None, command.exec(None));
     assert_eq!(InputMode::Error, command.input_mode);
+
+    command.input = ":g -i ".to_string();
+    assert_eq!(None, command.exec(None));
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    command.input = ":g fn main".to_string();
+    assert_eq!(Some(AppCommand::Grep {
+        pattern: "fn main".to_string(),
+        ignore_case: false,
+    }), command.exec(None));
+    assert_eq!(InputMode::Normal, command.input_mode);
+
+    command.input = ":g -i todo".to_string();
+    assert_eq!(Some(AppCommand::Grep {
+        pattern: "todo".to_string(),
+        ignore_case: true,
+    }), command.exec(None));
+    assert!(command.input.is_empty());
+}
+
+#[test]
+fn test_bookmark_command() {
+    let mut command = CommandHandler::default();
+
+    command.input = ":b ".to_string();
+    assert_eq!(None, command.exec(None));
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    command.input = ":b work".to_string();
+    assert_eq!(Some(AppCommand::Bookmark {
+        name: "work".to_string(),
+    }), command.exec(None));
+    assert_eq!(InputMode::Normal, command.input_mode);
+}
+
+#[test]
+fn test_jump_command() {
+    let mut command = CommandHandler::default();
+
+    command.input = ":z".to_string();
+    assert_eq!(None, command.exec(None));
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    command.input = ":z foo  src".to_string();
+    assert_eq!(Some(AppCommand::Jump {
+        fragments: vec!["foo".to_string(), "src".to_string()],
+    }), command.exec(None));
+}
+
+#[test]
+fn test_read_only() {
+    let mut command = CommandHandler::default();
+    command.read_only = true;
+
+    command.input = ":n f rufile_read_only".to_string();
+    assert_eq!(None, command.exec(None));
+    assert_eq!(InputMode::Error, command.input_mode);
+    assert_eq!(Some("read-only mode".to_string()), command.error);
+    assert!(!Path::new("rufile_read_only").exists());
+
+    command.input = ":z src".to_string();
+    assert!(command.exec(None).is_some());
+}
+
+#[test]
+fn test_select_command() {
+    let mut command = CommandHandler::default();
+
+    command.input = ":s".to_string();
+    assert_eq!(None, command.exec(None));
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    command.input = ":s *.rs".to_string();
+    assert_eq!(Some(AppCommand::Select {
+        pattern: "*.rs".to_string(),
+    }), command.exec(None));
+    assert_eq!(InputMode::Normal, command.input_mode);
+}
+
+#[test]
+fn test_several_files() {
+    let dir = TempDir::new("several_files");
+    let files = [dir.join("a"), dir.join("b")];
+    for file in &files {
+        fs::write(file, "").unwrap();
+    }
+    let names: Vec<&OsStr> = files.iter().map(|file| file.as_os_str()).collect();
+
+    let mut command = CommandHandler::default();
+
+    command.input = ":e 600".to_string();
+    command.exec_on(&names);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    for file in &files {
+        assert_eq!(0o600, fs::metadata(file).unwrap().permissions().mode() & 0o777);
+    }
+
+    command.input = ":r c".to_string();
+    command.exec_on(&names);
+    assert_eq!(InputMode::Error, command.input_mode);
+    assert_eq!(Some("can't rename several files at once".to_string()), command.error);
+
+    command.input = ":d".to_string();
+    command.exec_on(&names);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert!(files.iter().all(|file| !file.exists()));
 }
\ No newline at end of file
@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+use std::{fs, thread, time::Duration};
+
+use rufile::search::finder::WalkOptions;
+use rufile::search::grep::{self, Grep};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_grep_files() {
+    let root = TempDir::new("grep");
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/main.rs"), "fn main() {\n    println!(\"Hello\");\n}\n").unwrap();
+    fs::write(root.join("notes.txt"), "hello hello\nbye\n").unwrap();
+    fs::write(root.join("data.bin"), b"hello\0\x01\x02\xff").unwrap();
+
+    let regex = grep::build_regex("hello", true).unwrap();
+    let mut job = Grep::spawn(root.clone(), regex, WalkOptions::default());
+
+    let mut hits = vec![];
+    while !job.is_done() {
+        hits.extend(job.poll());
+        thread::sleep(Duration::from_millis(5));
+    }
+    hits.sort_by(|a, b| a.path.cmp(&b.path));
+
+    assert_eq!(2, hits.len());
+    assert_eq!(PathBuf::from("notes.txt"), hits[0].path);
+    assert_eq!(1, hits[0].line);
+    assert_eq!(vec![0..5, 6..11], hits[0].matches);
+    assert_eq!(PathBuf::from("src/main.rs"), hits[1].path);
+    assert_eq!(2, hits[1].line);
+    assert_eq!("    println!(\"Hello\");", hits[1].text);
+
+    let context = grep::context(&root.join("src/main.rs"), 1, 1).unwrap();
+    assert_eq!(vec![1, 2], context.iter().map(|(line, _)| *line).collect::<Vec<_>>());
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use std::fs;
+
+use rufile::entry::listing::DirListing;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_listing_refresh() {
+    let dir = TempDir::new("listing");
+    fs::File::create(dir.join("a")).unwrap();
+
+    let mut listing = DirListing::default();
+    listing.load(&dir).unwrap();
+    assert_eq!(1, listing.len());
+
+    assert!(!listing.refresh().unwrap());
+
+    fs::File::create(dir.join("b")).unwrap();
+    assert!(listing.refresh().unwrap());
+    assert_eq!(2, listing.len());
+
+    fs::remove_file(dir.join("a")).unwrap();
+    listing.invalidate();
+    assert!(listing.refresh().unwrap());
+    assert_eq!(1, listing.len());
+    assert_eq!("b", listing.files()[0].name);
+    // read wherever the process is
+    fs::write(dir.join("b"), "first\nsecond").unwrap();
+    assert_eq!(dir.join("b"), listing.files()[0].path());
+    assert_eq!("first", listing.files()[0].preview_lines(1).unwrap());
+}
+
+#[test]
+fn test_listing_vanished() {
+    let dir = TempDir::new("listing_vanished");
+
+    let mut listing = DirListing::default();
+    listing.load(&dir).unwrap();
+    assert!(!listing.refresh().unwrap());
+
+    fs::remove_dir(&dir).unwrap();
+    assert!(listing.refresh().is_err());
+}
\ No newline at end of file
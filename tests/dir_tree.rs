@@ -0,0 +1,53 @@
+use std::fs;
+
+use rufile::entry::listing::read_dir;
+use rufile::entry::sort::SortOrder;
+use rufile::entry::tree::Tree;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_tree_flatten() {
+    let dir = TempDir::new("tree");
+    fs::create_dir_all(dir.join("a").join("b")).unwrap();
+    fs::File::create(dir.join("a").join("b").join("c")).unwrap();
+    fs::File::create(dir.join("a").join(".hidden")).unwrap();
+    fs::File::create(dir.join("a").join("d")).unwrap();
+    fs::File::create(dir.join("e")).unwrap();
+
+    let sort = SortOrder::default();
+    let mut top = read_dir(&dir).unwrap();
+    sort.sort(&mut top);
+    let top: Vec<_> = top.iter().collect();
+
+    let mut tree = Tree::default();
+    let hidden = |file: &rufile::entry::file_data::FileData| file.name.ends_with(".hidden");
+    assert_eq!(2, tree.flatten(&top, hidden).len());
+
+    tree.expand(&dir, "a", sort).unwrap();
+    tree.expand(&dir, "a/b", sort).unwrap();
+    let rows = tree.flatten(&top, hidden);
+    let names: Vec<_> = rows
+        .iter()
+        .map(|row| match &row.parent {
+            None => top[row.idx].name.clone(),
+            Some(parent) => tree.children(parent)[row.idx].name.clone(),
+        })
+        .collect();
+    assert_eq!(vec!["a", "a/b", "a/b/c", "a/d", "e"], names);
+    assert_eq!(vec![0, 1, 2, 1, 0], rows.iter().map(|row| row.depth).collect::<Vec<_>>());
+    assert_eq!(vec![true, true], rows[2].guides);
+    assert_eq!(vec![true], rows[3].guides);
+    assert!(rows[2].last && !rows[1].last && rows[3].last);
+
+    // collapsing a directory collapses everything below it
+    tree.collapse("a");
+    assert!(!tree.is_expanded("a/b"));
+    assert_eq!(2, tree.flatten(&top, hidden).len());
+
+    tree.expand(&dir, "a", sort).unwrap();
+    fs::remove_dir_all(dir.join("a")).unwrap();
+    tree.reload(&dir, sort);
+    assert!(tree.is_empty());
+}
\ No newline at end of file
@@ -0,0 +1,46 @@
+use std::path::{Path, PathBuf};
+use std::{fs, thread, time::Duration};
+
+use rufile::search::finder::{Finder, WalkOptions};
+use rufile::search::matcher::{MatchKind, Matcher};
+
+mod common;
+use common::TempDir;
+
+fn find(root: &Path, pattern: &str, options: WalkOptions) -> Vec<PathBuf> {
+    let matcher = Matcher::new(MatchKind::Glob, pattern).unwrap();
+    let mut finder = Finder::spawn(root.to_path_buf(), matcher, options);
+
+    let mut found = vec![];
+    while !finder.is_done() {
+        found.extend(finder.poll().into_iter().map(|result| result.path));
+        thread::sleep(Duration::from_millis(5));
+    }
+    found.sort();
+
+    found
+}
+
+#[test]
+fn test_find_files() {
+    let root = TempDir::new("find");
+    fs::create_dir_all(root.join("a/b/c")).unwrap();
+    fs::create_dir_all(root.join(".hidden")).unwrap();
+    fs::write(root.join("top.rs"), "").unwrap();
+    fs::write(root.join("a/mid.rs"), "").unwrap();
+    fs::write(root.join("a/b/c/deep.rs"), "").unwrap();
+    fs::write(root.join(".hidden/secret.rs"), "").unwrap();
+
+    let all = find(&root, "*.rs", WalkOptions::default());
+    assert_eq!(vec![
+        PathBuf::from("a/b/c/deep.rs"),
+        PathBuf::from("a/mid.rs"),
+        PathBuf::from("top.rs"),
+    ], all);
+
+    let shallow = find(&root, "*.rs", WalkOptions { max_depth: 2, show_hidden: false });
+    assert_eq!(vec![PathBuf::from("a/mid.rs"), PathBuf::from("top.rs")], shallow);
+
+    let hidden = find(&root, "s*", WalkOptions { max_depth: 8, show_hidden: true });
+    assert_eq!(vec![PathBuf::from(".hidden/secret.rs")], hidden);
+}
\ No newline at end of file
@@ -0,0 +1,59 @@
+use std::cmp::Ordering;
+use std::fs;
+
+use rufile::entry::listing::DirListing;
+use rufile::entry::sort::{natural_cmp, SortKey, SortOrder};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_natural_cmp() {
+    assert_eq!(Ordering::Less, natural_cmp("file2", "file10"));
+    assert_eq!(Ordering::Greater, natural_cmp("file10", "file2"));
+    assert_eq!(Ordering::Equal, natural_cmp("file10", "file10"));
+    assert_eq!(Ordering::Less, natural_cmp("file", "file1"));
+    assert_eq!(Ordering::Less, natural_cmp("1", "01"));
+    assert_eq!(Ordering::Less, natural_cmp("a9b", "a10a"));
+    assert_eq!(Ordering::Less, natural_cmp("abc", "abd"));
+}
+
+#[test]
+fn test_sort_order() {
+    let dir = TempDir::new("sort");
+    fs::create_dir_all(dir.join("zdir")).unwrap();
+    fs::write(dir.join("b10.txt"), "1234").unwrap();
+    fs::write(dir.join("B2.rs"), "12").unwrap();
+    fs::write(dir.join("a.md"), "123456").unwrap();
+
+    let names = |listing: &DirListing| -> Vec<String> {
+        listing.files().iter().map(|file| file.name.clone()).collect()
+    };
+
+    let mut listing = DirListing::default();
+    listing.load(&dir).unwrap();
+    assert_eq!(vec!["zdir", "a.md", "B2.rs", "b10.txt"], names(&listing));
+
+    let mut sort = SortOrder {
+        key: SortKey::Size,
+        ..SortOrder::default()
+    };
+    listing.set_sort(sort);
+    assert_eq!(vec!["zdir", "B2.rs", "b10.txt", "a.md"], names(&listing));
+
+    sort.reverse = true;
+    listing.set_sort(sort);
+    assert_eq!(vec!["zdir", "a.md", "b10.txt", "B2.rs"], names(&listing));
+
+    sort = SortOrder {
+        key: SortKey::Extension,
+        ..SortOrder::default()
+    };
+    listing.set_sort(sort);
+    assert_eq!(vec!["zdir", "a.md", "B2.rs", "b10.txt"], names(&listing));
+
+    sort.key = SortKey::Name;
+    sort.ignore_case = false;
+    listing.set_sort(sort);
+    assert_eq!(vec!["zdir", "B2.rs", "a.md", "b10.txt"], names(&listing));
+}
\ No newline at end of file
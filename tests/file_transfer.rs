@@ -0,0 +1,76 @@
+use std::ffi::OsStr;
+use std::fs;
+
+use rufile::command_input::input::{CommandHandler, InputMode};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_copy_and_move_to_destination() {
+    let dir = TempDir::new("transfer");
+    let (left, right) = (dir.join("left"), dir.join("right"));
+    fs::create_dir_all(left.join("sub")).unwrap();
+    fs::create_dir_all(&right).unwrap();
+    fs::write(left.join("a"), "a").unwrap();
+    fs::write(left.join("sub").join("b"), "b").unwrap();
+
+    let file = left.join("a");
+    let sub = left.join("sub");
+
+    let mut command = CommandHandler::default();
+    command.destination = Some(right.clone());
+
+    command.input = ":c".to_string();
+    command.exec(Some(file.as_os_str()));
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!("a", fs::read_to_string(right.join("a")).unwrap());
+    assert!(file.exists());
+
+    // the copy is there now
+    command.input = ":c".to_string();
+    command.exec(Some(file.as_os_str()));
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    // a file can't be copied onto itself, a directory not into itself
+    command.input = format!(":c {}", left.display());
+    command.exec(Some(file.as_os_str()));
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    command.input = format!(":c {}", sub.display());
+    command.exec(Some(sub.as_os_str()));
+    assert_eq!(InputMode::Error, command.input_mode);
+
+    command.input = ":m".to_string();
+    command.exec(Some(sub.as_os_str()));
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!("b", fs::read_to_string(right.join("sub").join("b")).unwrap());
+    assert!(!sub.exists());
+
+    command.input = ":c a b".to_string();
+    command.exec(Some(file.as_os_str()));
+    assert_eq!(InputMode::Error, command.input_mode);
+}
+
+#[test]
+fn test_copy_several_to_destination() {
+    let dir = TempDir::new("transfer_several");
+    let (left, right) = (dir.join("left"), dir.join("right"));
+    fs::create_dir_all(left.join("sub")).unwrap();
+    fs::create_dir_all(&right).unwrap();
+    fs::write(left.join("a"), "a").unwrap();
+    fs::write(left.join("sub").join("b"), "b").unwrap();
+
+    let files = [left.join("a"), left.join("sub")];
+    let names: Vec<&OsStr> = files.iter().map(|file| file.as_os_str()).collect();
+
+    let mut command = CommandHandler::default();
+    command.destination = Some(right.clone());
+
+    command.input = ":m".to_string();
+    command.exec_on(&names);
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert_eq!("a", fs::read_to_string(right.join("a")).unwrap());
+    assert_eq!("b", fs::read_to_string(right.join("sub").join("b")).unwrap());
+    assert!(files.iter().all(|file| !file.exists()));
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::fs;
+
+use rufile::frecency::Frecency;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_frecency_query() {
+    let dir = TempDir::new("frecency");
+    let (src, project) = (dir.join("src"), dir.join("Project").join("src"));
+    fs::create_dir_all(&src).unwrap();
+    fs::create_dir_all(&project).unwrap();
+    let gone = dir.join("gone");
+
+    let now = 10_000_000;
+    let mut frecency = Frecency::default();
+    frecency.add(&src, now - 30 * 24 * 60 * 60);
+    frecency.add(&src, now - 30 * 24 * 60 * 60);
+    frecency.add(&project, now - 60);
+    frecency.add(&gone, now);
+
+    // a recent visit beats an old one with a higher rank
+    assert_eq!(Some(project.as_path()), frecency.query(&["src"], &dir, now));
+    assert_eq!(Some(src.as_path()), frecency.query(&["src"], &project, now));
+    assert_eq!(Some(project.as_path()), frecency.query(&["proj", "SRC"], &dir, now));
+    // the last fragment has to match the last component
+    assert_eq!(None, frecency.query(&["project"], &dir, now));
+    // missing directories are skipped
+    assert_eq!(None, frecency.query(&["gone"], &dir, now));
+
+    let file = dir.join("frecency.toml");
+    let mut saved = Frecency::load(&file).unwrap();
+    saved.add(&src, now);
+    saved.save().unwrap();
+    let loaded = Frecency::load(&file).unwrap();
+    assert_eq!(1, loaded.visits().len());
+    assert_eq!(src, loaded.visits()[0].path);
+}
\ No newline at end of file
@@ -0,0 +1,54 @@
+use std::fs;
+
+use rufile::config::Config;
+use rufile::entry::listing::DirListing;
+use rufile::entry::visibility::Visibility;
+use rufile::search::filter::filter_files;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_view_config() {
+    let config = Config::parse("[view]\nshow_hidden = true\nignore = [\"*.o\"]\n").unwrap();
+    assert!(config.view.show_hidden);
+    assert!(!config.view.gitignore);
+    assert_eq!(vec!["*.o"], config.view.ignore);
+
+    assert!(Config::parse("").is_ok());
+    assert!(Config::parse("[view]\nhidden = true\n").is_err());
+}
+
+#[test]
+fn test_hidden_entries() {
+    let dir = TempDir::new("hidden");
+    fs::create_dir_all(dir.join(".git")).unwrap();
+    fs::create_dir_all(dir.join("target")).unwrap();
+    fs::write(dir.join(".gitignore"), "*.log\n").unwrap();
+    fs::write(dir.join("main.o"), "").unwrap();
+    fs::write(dir.join("build.log"), "").unwrap();
+    fs::write(dir.join("main.rs"), "").unwrap();
+
+    let mut listing = DirListing::default();
+    listing.load(&dir).unwrap();
+
+    let config = Config::parse("[view]\nignore = [\"*.o\", \"target/\"]\ngitignore = true\n").unwrap();
+    let mut visibility = Visibility::new(&config.view);
+    visibility.set_dir(&dir);
+
+    let visible = |visibility: &Visibility| {
+        let (entries, hidden) = filter_files(listing.files(), &dir, visibility, None);
+        let names: Vec<_> = entries.iter().map(|entry| listing.files()[entry.idx].name.clone()).collect();
+        (names, hidden)
+    };
+
+    assert_eq!((vec!["main.rs".to_string()], 5), visible(&visibility));
+
+    visibility.show_hidden = true;
+    let (names, hidden) = visible(&visibility);
+    assert_eq!(3, hidden);
+    assert!(names.contains(&".gitignore".to_string()));
+
+    visibility.apply_ignores = false;
+    assert_eq!(0, visible(&visibility).1);
+}
\ No newline at end of file
@@ -0,0 +1,177 @@
+use std::os::unix::fs::symlink;
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::{fs, io, thread};
+
+use rufile::command_input::input::CommandHandler;
+use rufile::command_input::jobs::{Job, JobControl, JobKind, JobQueue, JobStatus, JobUpdate, Progress};
+
+mod common;
+use common::TempDir;
+
+fn temp_dir(name: &str) -> TempDir {
+    let dir = TempDir::new(&format!("jobs_{}", name));
+    fs::create_dir_all(dir.join("src/sub")).unwrap();
+    fs::create_dir_all(dir.join("dst")).unwrap();
+    fs::write(dir.join("src/a"), "aaaa").unwrap();
+    fs::write(dir.join("src/sub/b"), "bb").unwrap();
+    symlink("a", dir.join("src/link")).unwrap();
+
+    dir
+}
+
+#[test]
+fn test_copy_progress() {
+    let dir = temp_dir("copy");
+    let job = Job::new(JobKind::Copy, vec![dir.join("src")], Some(dir.join("dst")));
+    assert_eq!("copy src to ".to_string() + &dir.join("dst").display().to_string(), job.to_string());
+
+    let mut reports = vec![];
+    job.run(&JobControl::default(), &mut |progress| reports.push(progress.clone())).unwrap();
+
+    let last = reports.last().unwrap();
+    assert_eq!((3, 3), (last.files_done, last.files_total));
+    assert_eq!((6, 6), (last.bytes_done, last.bytes_total));
+    assert_eq!(1.0, last.ratio());
+    assert_eq!("aaaa", fs::read_to_string(dir.join("dst/src/a")).unwrap());
+    assert_eq!("bb", fs::read_to_string(dir.join("dst/src/sub/b")).unwrap());
+    assert_eq!(PathBuf::from("a"), fs::read_link(dir.join("dst/src/link")).unwrap());
+
+    // the copy is there now
+    let err = job.run(&JobControl::default(), &mut |_| {}).unwrap_err();
+    assert_eq!(io::ErrorKind::AlreadyExists, err.kind());
+}
+
+#[test]
+fn test_cancel() {
+    let dir = temp_dir("cancel");
+    let job = Job::new(JobKind::Move, vec![dir.join("src")], Some(dir.join("dst")));
+
+    // a cancelled job doesn't start
+    let control = JobControl::default();
+    control.pause();
+    control.cancel();
+    assert!(!control.is_paused());
+    let err = job.run(&control, &mut |_| {}).unwrap_err();
+    assert_eq!(io::ErrorKind::Interrupted, err.kind());
+    assert!(!dir.join("dst/src").exists());
+    assert!(dir.join("src/a").exists());
+}
+
+#[test]
+fn test_move_checks_all_destinations() {
+    let dir = temp_dir("move_check");
+    fs::write(dir.join("dst/a"), "taken").unwrap();
+    let job = Job::new(JobKind::Move, vec![dir.join("src/sub"), dir.join("src/a")], Some(dir.join("dst")));
+
+    let err = job.run(&JobControl::default(), &mut |_| {}).unwrap_err();
+    assert_eq!(io::ErrorKind::AlreadyExists, err.kind());
+
+    // the first source wasn't moved before the second one failed
+    assert!(dir.join("src/sub/b").exists());
+    assert!(!dir.join("dst/sub").exists());
+}
+
+#[test]
+fn test_failed_copy() {
+    let dir = temp_dir("failed");
+    // sockets can't be opened like files
+    let _socket = UnixListener::bind(dir.join("src/sub/socket")).unwrap();
+    let job = Job::new(JobKind::Copy, vec![dir.join("src")], Some(dir.join("dst")));
+
+    assert!(job.run(&JobControl::default(), &mut |_| {}).is_err());
+
+    // the partial copy is gone, the source is whole
+    assert!(!dir.join("dst/src").exists());
+    assert_eq!("aaaa", fs::read_to_string(dir.join("src/a")).unwrap());
+}
+
+#[test]
+fn test_queue() {
+    let dir = temp_dir("queue");
+    let (tx, rx) = mpsc::channel();
+
+    let mut queue = JobQueue::default();
+    let first = queue.push(Job::new(JobKind::Copy, vec![dir.join("src")], Some(dir.join("dst"))));
+    let second = queue.push(Job::new(JobKind::Delete, vec![dir.join("src")], None));
+    let third = queue.push(Job::new(JobKind::Delete, vec![dir.join("dst")], None));
+    queue.cancel(2);
+    queue.toggle_pause(0);
+
+    let report = move |id, update| { tx.send((id, update)).ok(); };
+    queue.start_next(report.clone());
+    assert!(queue.is_busy());
+    assert_eq!(JobStatus::Running, queue.get(first).unwrap().status);
+    assert_eq!(JobStatus::Queued, queue.get(second).unwrap().status);
+    assert_eq!(JobStatus::Cancelled, queue.get(third).unwrap().status);
+
+    // nothing happens while paused
+    thread::sleep(Duration::from_millis(50));
+    assert!(!dir.join("dst/src").exists());
+    queue.toggle_pause(0);
+
+    for id in [first, second] {
+        loop {
+            let (from, update) = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+            assert_eq!(id, from);
+            let finished = !matches!(update, JobUpdate::Progress(_));
+            queue.update(from, update);
+            if finished {
+                break;
+            }
+        }
+        assert_eq!(JobStatus::Done, queue.get(id).unwrap().status);
+        queue.start_next(report.clone());
+    }
+
+    assert!(!queue.is_busy());
+    assert!(!dir.join("src").exists());
+    assert_eq!("bb", fs::read_to_string(dir.join("dst/src/sub/b")).unwrap());
+
+    queue.clear_finished();
+    assert!(queue.entries().is_empty());
+}
+
+#[test]
+fn test_eta() {
+    let mut progress = Progress {
+        files_total: 4,
+        elapsed: Duration::from_secs(3),
+        ..Progress::default()
+    };
+    assert_eq!(None, progress.eta());
+
+    progress.files_done = 1;
+    assert_eq!(Some(Duration::from_secs(9)), progress.eta());
+
+    // bytes count once there are any
+    progress.bytes_total = 100;
+    progress.bytes_done = 75;
+    assert_eq!(0.75, progress.ratio());
+    assert_eq!(Some(Duration::from_secs(1)), progress.eta());
+}
+
+#[test]
+fn test_background_commands() {
+    let dir = temp_dir("background");
+    let files = [dir.join("src/a"), dir.join("src/sub")];
+    let names: Vec<_> = files.iter().map(|file| file.as_os_str()).collect();
+
+    let mut command = CommandHandler::default();
+    command.background = true;
+
+    command.input = ":d".to_string();
+    command.exec_on(&names);
+    assert!(files.iter().all(|file| file.exists()));
+    assert_eq!(vec![Job::new(JobKind::Delete, files.to_vec(), None)], command.take_jobs());
+    assert!(command.take_jobs().is_empty());
+
+    // a file that can't be copied leaves none queued
+    command.destination = Some(dir.join("dst"));
+    let missing = dir.join("missing");
+    command.input = ":c".to_string();
+    command.exec_on(&[files[0].as_os_str(), missing.as_os_str()]);
+    assert!(command.take_jobs().is_empty());
+}
\ No newline at end of file
@@ -0,0 +1,54 @@
+use termion::event::Key;
+
+use rufile::config::Config;
+use rufile::keymap::{parse_keys, Action, Keymap, KeymapError, Lookup};
+
+#[test]
+fn test_parse_keys() {
+    assert_eq!(Some(vec![Key::Char('g'), Key::Char('g')]), parse_keys("gg"));
+    assert_eq!(Some(vec![Key::Ctrl('d')]), parse_keys("<C-d>"));
+    assert_eq!(Some(vec![Key::Alt('1')]), parse_keys("<A-1>"));
+    assert_eq!(Some(vec![Key::Char(' '), Key::Char('<')]), parse_keys("<Space><lt>"));
+    assert_eq!(Some(vec![Key::Char('\n')]), parse_keys("<CR>"));
+    assert_eq!(None, parse_keys(""));
+    assert_eq!(None, parse_keys("5j"));
+    assert_eq!(None, parse_keys("<C-d"));
+    assert_eq!(None, parse_keys("<Hyper-x>"));
+}
+
+#[test]
+fn test_default_keymap() {
+    let keymap = Keymap::default();
+    assert_eq!(Lookup::Action(Action::Down), keymap.lookup(&[Key::Char('j')]));
+    assert_eq!(Lookup::Prefix, keymap.lookup(&[Key::Char('g')]));
+    assert_eq!(Lookup::Action(Action::Top), keymap.lookup(&[Key::Char('g'), Key::Char('g')]));
+    assert_eq!(Lookup::Action(Action::Tab(2)), keymap.lookup(&[Key::Alt('3')]));
+    assert_eq!(Lookup::None, keymap.lookup(&[Key::Char('z')]));
+}
+
+#[test]
+fn test_keymap_from_config() {
+    let config = Config::parse("[keys]\n\"<C-n>\" = \"down\"\nj = \"none\"\n\"gh\" = \"back\"\n").unwrap();
+    let keymap = Keymap::new(&config.keys).unwrap();
+    assert_eq!(Lookup::Action(Action::Down), keymap.lookup(&[Key::Ctrl('n')]));
+    assert_eq!(Lookup::None, keymap.lookup(&[Key::Char('j')]));
+    assert_eq!(Lookup::Action(Action::Back), keymap.lookup(&[Key::Char('g'), Key::Char('h')]));
+
+    let config = Config::parse("[keys]\nx = \"explode\"\n").unwrap();
+    assert_eq!(KeymapError::UnknownAction {
+        keys: "x".to_string(),
+        action: "explode".to_string(),
+    }, Keymap::new(&config.keys).unwrap_err());
+
+    let config = Config::parse("[keys]\ng = \"top\"\n").unwrap();
+    assert_eq!(KeymapError::Conflict {
+        keys: "g".to_string(),
+        other: "gg".to_string(),
+    }, Keymap::new(&config.keys).unwrap_err());
+
+    let config = Config::parse("[keys]\ng = \"top\"\ngg = \"none\"\n").unwrap();
+    assert!(Keymap::new(&config.keys).is_ok());
+
+    let config = Config::parse("[keys]\n\"<Nope>\" = \"top\"\n").unwrap();
+    assert!(matches!(Keymap::new(&config.keys), Err(KeymapError::InvalidKey { .. })));
+}
\ No newline at end of file
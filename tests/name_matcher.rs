@@ -0,0 +1,68 @@
+use rufile::search::matcher::{MatchKind, Matcher};
+
+#[test]
+fn test_substring_match() {
+    let matcher = Matcher::new(MatchKind::Substring, "ain").unwrap();
+
+    assert_eq!(vec![1, 2, 3], matcher.matches("main.rs").unwrap().positions);
+    assert_eq!(vec![1, 2, 3], matcher.matches("MAIN.rs").unwrap().positions);
+    assert!(matcher.matches("mai.rs").is_none());
+
+    let matcher = Matcher::new(MatchKind::Substring, "Main").unwrap();
+    assert!(matcher.matches("main.rs").is_none());
+    assert!(matcher.matches("Main.rs").is_some());
+}
+
+#[test]
+fn test_glob_match() {
+    let matcher = Matcher::new(MatchKind::Glob, "*.rs").unwrap();
+    assert_eq!(vec![4, 5, 6], matcher.matches("main.rs").unwrap().positions);
+    assert!(matcher.matches("main.rsx").is_none());
+
+    let matcher = Matcher::new(MatchKind::Glob, "file?.[a-c]").unwrap();
+    assert!(matcher.matches("file1.b").is_some());
+    assert!(matcher.matches("file12.b").is_none());
+    assert!(matcher.matches("file1.d").is_none());
+
+    let matcher = Matcher::new(MatchKind::Glob, "[!m]*").unwrap();
+    assert!(matcher.matches("lib.rs").is_some());
+    assert!(matcher.matches("main.rs").is_none());
+
+    let matcher = Matcher::new(MatchKind::Glob, "[abc").unwrap();
+    assert!(matcher.matches("[abc").is_some());
+
+    let matcher = Matcher::new(MatchKind::Glob, "a*b*c").unwrap();
+    assert_eq!(vec![0, 3, 6], matcher.matches("axxbxxc").unwrap().positions);
+    assert!(matcher.matches("abcx").is_none());
+
+    // would take ages if every `*` retried every length
+    let matcher = Matcher::new(MatchKind::Glob, "*a*a*a*a*a*a*a*b").unwrap();
+    assert!(matcher.matches(&"a".repeat(200)).is_none());
+}
+
+#[test]
+fn test_regex_match() {
+    let matcher = Matcher::new(MatchKind::Regex, r"^\w+\.rs$").unwrap();
+    assert_eq!(vec![0, 1, 2, 3, 4, 5, 6], matcher.matches("MAIN.rs").unwrap().positions);
+    assert!(matcher.matches("main.rs.bak").is_none());
+
+    let matcher = Matcher::new(MatchKind::Regex, "ö+").unwrap();
+    assert_eq!(vec![1, 2], matcher.matches("föö").unwrap().positions);
+
+    assert!(Matcher::new(MatchKind::Regex, "(unclosed").is_err());
+}
+
+#[test]
+fn test_fuzzy_match() {
+    let matcher = Matcher::new(MatchKind::Fuzzy, "fb").unwrap();
+
+    assert_eq!(vec![0, 4], matcher.matches("foo_bar").unwrap().positions);
+    assert!(matcher.matches("bar_foo").is_none());
+
+    let prefix = matcher.matches("fbar").unwrap();
+    let scattered = matcher.matches("xxfxxxxb").unwrap();
+    assert!(prefix.score > scattered.score);
+
+    let matcher = Matcher::new(MatchKind::Fuzzy, "").unwrap();
+    assert!(matcher.matches("anything").is_some());
+}
\ No newline at end of file
@@ -0,0 +1,60 @@
+use std::ffi::{OsStr, OsString};
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+use std::fs;
+
+use rufile::command_input::input::{CommandHandler, InputMode};
+use rufile::entry::file_data::{display_name, escape_name};
+use rufile::entry::listing::read_dir;
+use rufile::entry::sort::SortOrder;
+use rufile::entry::tree::Tree;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_escape_name() {
+    assert_eq!("plain", display_name(OsStr::new("plain")));
+    assert_eq!("caf\\xE9", display_name(OsStr::from_bytes(b"caf\xe9")));
+
+    let (name, escaped) = escape_name(OsStr::from_bytes(b"\xff\xfe\xc3\xa9x"));
+    assert_eq!("\\xFF\\xFEéx", name);
+    assert_eq!((0..8).collect::<Vec<_>>(), escaped);
+
+    // a name spelling out an escape differs from the byte it stands for
+    let (name, escaped) = escape_name(OsStr::new("\\xFF"));
+    assert_eq!("\\\\xFF", name);
+    assert_eq!(vec![0, 1], escaped);
+    assert_ne!(display_name(OsStr::new("\\xFF")), display_name(OsStr::from_bytes(b"\xff")));
+}
+
+#[test]
+fn test_non_utf8_entries() {
+    let dir = TempDir::new("non_utf8");
+    let raw_dir = OsString::from_vec(b"d\xe9".to_vec());
+    fs::create_dir_all(dir.join(&raw_dir)).unwrap();
+    fs::File::create(dir.join(&raw_dir).join(OsStr::from_bytes(b"f\xe9"))).unwrap();
+
+    let files = read_dir(&dir).unwrap();
+    assert_eq!("d\\xE9", files[0].name);
+    assert_eq!(raw_dir, files[0].raw_name());
+
+    let mut tree = Tree::default();
+    tree.expand(&dir, files[0].raw_name(), SortOrder::default()).unwrap();
+    assert!(tree.is_expanded("d\\xE9"));
+    let child = &tree.children("d\\xE9")[0];
+    assert_eq!("d\\xE9/f\\xE9", child.name);
+    assert!(dir.join(child.raw_name()).is_file());
+
+    let mut command = CommandHandler::default();
+    let renamed = dir.join("plain");
+    command.input = format!(":r {}", renamed.display());
+    command.exec(Some(dir.join(&raw_dir).as_os_str()));
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert!(renamed.is_dir());
+
+    fs::rename(&renamed, dir.join(&raw_dir)).unwrap();
+    command.input = ":d".to_string();
+    command.exec(Some(dir.join(&raw_dir).as_os_str()));
+    assert_eq!(InputMode::Normal, command.input_mode);
+    assert!(!dir.join(&raw_dir).exists());
+}
\ No newline at end of file
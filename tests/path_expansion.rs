@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use rufile::command_input::input::{AppCommand, CommandHandler, InputMode};
+use rufile::command_input::path::{complete_dir, expand, normalize};
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_expand() {
+    env::set_var("RUFILE_TEST_DIR", "/srv/data");
+    let home = dirs::home_dir().unwrap();
+
+    assert_eq!(PathBuf::from("/srv/data/x"), expand("$RUFILE_TEST_DIR/x").unwrap());
+    assert_eq!(PathBuf::from("/srv/data_x"), expand("${RUFILE_TEST_DIR}_x").unwrap());
+    assert_eq!(home.join("src"), expand("~/src").unwrap());
+    assert_eq!(home, expand("~").unwrap());
+    assert_eq!(PathBuf::from("~user/a$"), expand("~user/a$").unwrap());
+    assert!(expand("$RUFILE_TEST_UNSET/x").is_err());
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(PathBuf::from("/usr/bin"), normalize(Path::new("/usr/./bin/")));
+    assert_eq!(PathBuf::from("/usr"), normalize(Path::new("/usr/bin/..")));
+    assert_eq!(PathBuf::from("/"), normalize(Path::new("/../..")));
+}
+
+#[test]
+fn test_complete_dir() {
+    let dir = TempDir::new("complete");
+    fs::create_dir_all(dir.join("projects").join("rufile")).unwrap();
+    fs::create_dir_all(dir.join("public")).unwrap();
+    fs::create_dir_all(dir.join(".private")).unwrap();
+    fs::File::create(dir.join("pfile")).unwrap();
+
+    assert_eq!(Some("p".to_string()), complete_dir("p", &dir));
+    assert_eq!(Some("projects/".to_string()), complete_dir("pr", &dir));
+    assert_eq!(Some("projects/rufile/".to_string()), complete_dir("projects/", &dir));
+    assert_eq!(Some(".private/".to_string()), complete_dir(".", &dir));
+    assert_eq!(None, complete_dir("x", &dir));
+
+    let absolute = format!("{}/pu", dir.display());
+    assert_eq!(Some(format!("{}/public/", dir.display())), complete_dir(&absolute, &dir));
+
+    let mut command = CommandHandler::default();
+    command.input = format!(":cd {}/projects/rufile/..", dir.display());
+    assert_eq!(Some(AppCommand::ChangeDir {
+        path: dir.join("projects"),
+    }), command.exec(None));
+
+    command.input = format!(":cd {}", dir.join("pfile").display());
+    assert_eq!(None, command.exec(None));
+    assert_eq!(InputMode::Error, command.input_mode);
+    assert!(command.error.is_some());
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::fs;
+
+use rufile::entry::listing::read_dir;
+use rufile::entry::type_parser::FileType;
+
+mod common;
+use common::TempDir;
+
+#[test]
+fn test_symlinks() {
+    let dir = TempDir::new("symlinks");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::File::create(dir.join("file")).unwrap();
+    symlink("sub", dir.join("to_sub")).unwrap();
+    symlink(dir.join("file"), dir.join("to_file")).unwrap();
+    symlink("missing", dir.join("broken")).unwrap();
+
+    let mut files = read_dir(&dir).unwrap();
+    files.sort_by(|a, b| a.name.cmp(&b.name));
+    let names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+    assert_eq!(vec!["broken", "file", "sub", "to_file", "to_sub"], names);
+
+    let (broken, file, sub, to_file, to_sub) = (&files[0], &files[1], &files[2], &files[3], &files[4]);
+
+    assert!(!file.is_link());
+    assert!(!sub.is_link());
+    assert_eq!(None, sub.link_target());
+
+    assert!(to_sub.is_link());
+    assert!(to_sub.is_dir());
+    assert!(!to_sub.is_broken_link());
+    assert_eq!(FileType::LNK, to_sub.file_type());
+    assert_eq!(Some(Path::new("sub")), to_sub.link_target());
+
+    assert!(to_file.is_file());
+    assert_eq!(Some(dir.join("file").as_path()), to_file.link_target());
+
+    assert!(broken.is_broken_link());
+    assert!(!broken.is_dir());
+    assert!(!broken.is_file());
+    assert!(broken.info().contains("-> missing\nbroken link"));
+}
\ No newline at end of file